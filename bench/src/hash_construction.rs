@@ -0,0 +1,37 @@
+use criterion::{black_box, BenchmarkId, Criterion};
+use rb_sys::hash::hash_from_pairs;
+use rb_sys::{rb_hash_aset, rb_hash_new, rb_int2inum, VALUE};
+
+pub fn run(c: &mut Criterion) {
+    let mut group = c.benchmark_group("hash construction");
+
+    for size in [8, 128, 4096] {
+        let pairs: Vec<[VALUE; 2]> = (0..size)
+            .map(|i| unsafe { [rb_int2inum(i), rb_int2inum(i)] })
+            .collect();
+
+        group.bench_with_input(
+            BenchmarkId::new("rb_hash_aset", size),
+            &pairs,
+            |b, pairs| {
+                b.iter(|| unsafe {
+                    let hash = rb_hash_new();
+
+                    for [key, value] in pairs {
+                        rb_hash_aset(hash, *key, *value);
+                    }
+
+                    black_box(hash)
+                })
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("hash_from_pairs", size),
+            &pairs,
+            |b, pairs| b.iter(|| unsafe { black_box(hash_from_pairs(pairs)) }),
+        );
+    }
+
+    group.finish();
+}
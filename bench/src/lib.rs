@@ -4,8 +4,10 @@ use rb_sys::stable_api::{get_default, get_fallback};
 use rb_sys::StableApiDefinition;
 
 pub mod baselines;
+pub mod hash_construction;
 pub mod stable_abi_rarray;
 pub mod stable_abi_rstring;
+pub mod string_append;
 
 pub trait StableApiBenchExt {
     fn bench_abi_function<O>(
@@ -52,5 +54,7 @@ criterion_group!(
     benches,
     stable_abi_rstring::run,
     stable_abi_rarray::run,
+    string_append::run,
+    hash_construction::run,
     baselines::run
 );
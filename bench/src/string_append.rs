@@ -0,0 +1,80 @@
+use criterion::{black_box, BenchmarkId, Criterion, Throughput};
+use rb_sys::string::{is_ascii_only, str_append_bytes, str_cat_ascii, str_reserve};
+use rb_sys::{rb_str_cat, rb_str_new, rb_utf8_str_new};
+
+pub fn run(c: &mut Criterion) {
+    let chunk = [b'a'; 64];
+    let ascii = std::str::from_utf8(&chunk).unwrap();
+    let mut group = c.benchmark_group("string append");
+
+    for count in [16usize, 256, 4096] {
+        group.throughput(Throughput::Bytes((count * chunk.len()) as _));
+
+        group.bench_with_input(
+            BenchmarkId::new("rb_str_cat", count),
+            &count,
+            |b, &count| {
+                b.iter(|| unsafe {
+                    let string = rb_str_new(std::ptr::null(), 0);
+
+                    for _ in 0..count {
+                        rb_str_cat(string, chunk.as_ptr() as _, chunk.len() as _);
+                    }
+
+                    black_box(string)
+                })
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("str_reserve + str_append_bytes", count),
+            &count,
+            |b, &count| {
+                b.iter(|| unsafe {
+                    let string = rb_str_new(std::ptr::null(), 0);
+                    str_reserve(string, count * chunk.len());
+
+                    for _ in 0..count {
+                        str_append_bytes(string, &chunk);
+                    }
+
+                    black_box(string)
+                })
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("rb_str_cat + is_ascii_only", count),
+            &count,
+            |b, &count| {
+                b.iter(|| unsafe {
+                    let string = rb_utf8_str_new(std::ptr::null(), 0);
+
+                    for _ in 0..count {
+                        rb_str_cat(string, ascii.as_ptr() as _, ascii.len() as _);
+                    }
+
+                    black_box(is_ascii_only(string))
+                })
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("str_cat_ascii + is_ascii_only", count),
+            &count,
+            |b, &count| {
+                b.iter(|| unsafe {
+                    let string = rb_utf8_str_new(std::ptr::null(), 0);
+
+                    for _ in 0..count {
+                        str_cat_ascii(string, ascii);
+                    }
+
+                    black_box(is_ascii_only(string))
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
@@ -4,6 +4,7 @@ mod stable_api;
 use crate::cc::Build;
 use crate::utils::is_msvc;
 use crate::{debug_log, RbConfig};
+use bindgen::RustTarget;
 use quote::ToTokens;
 use stable_api::{categorize_bindings, opaqueify_bindings};
 use std::fs::File;
@@ -14,6 +15,9 @@ use syn::{Expr, ExprLit, ItemConst, Lit};
 
 const WRAPPER_H_CONTENT: &str = include_str!("bindings/wrapper.h");
 
+/// The MSRV of `rb-sys`, which the generated bindings target by default.
+const DEFAULT_RUST_TARGET: &str = "1.65";
+
 /// Generate bindings for the Ruby using bindgen.
 pub fn generate(
     rbconfig: &RbConfig,
@@ -21,7 +25,91 @@ pub fn generate(
     cfg_out: &mut File,
 ) -> Result<PathBuf, Box<dyn Error>> {
     let out_dir = PathBuf::from(env::var("OUT_DIR")?);
+    let slug = rbconfig.ruby_version_slug();
+    let crate_version = env!("CARGO_PKG_VERSION");
+    let out_path = out_dir.join(format!("bindings-{}-{}.rs", crate_version, slug));
+
+    if let Some(prebuilt_path) = prebuilt_bindings_path() {
+        return copy_prebuilt_bindings(rbconfig, &prebuilt_path, &out_path, cfg_out);
+    }
+
+    run_bindgen(rbconfig, static_ruby, &out_path, Some(cfg_out))
+}
+
+/// Bindings generated for a specific Ruby version by [`generate_versioned`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionedBindings {
+    /// The name of the module to include the bindings in (i.e. `ruby_3_3`).
+    pub module_name: String,
+    /// The path of the generated bindings.
+    pub path: PathBuf,
+}
+
+/// Generates bindings for the Ruby described by `rbconfig` (i.e. one loaded
+/// with [`RbConfig::from_pkg_config`]), which may not be the Ruby the crate is
+/// being built for, so that an extension can carry the bindings of more than
+/// one Ruby version.
+///
+/// Unlike [`generate`], no cargo cfgs are emitted for the bindings (they
+/// describe the current Ruby only), and prebuilt bindings are not used. Use
+/// [`write_versioned_modules`] to include the generated bindings in modules
+/// named after their version. Selecting the right module at runtime, and
+/// linking, are left to the extension.
+pub fn generate_versioned(
+    rbconfig: &RbConfig,
+    static_ruby: bool,
+) -> Result<VersionedBindings, Box<dyn Error>> {
+    let (major, minor) = rbconfig
+        .major_minor()
+        .ok_or("could not determine the Ruby version to tag the bindings with")?;
+    let out_dir = PathBuf::from(env::var("OUT_DIR")?);
+    let out_path = out_dir.join(format!(
+        "versioned-bindings-{}-{}.rs",
+        env!("CARGO_PKG_VERSION"),
+        rbconfig.ruby_version_slug()
+    ));
+
+    Ok(VersionedBindings {
+        module_name: versioned_module_name(major, minor),
+        path: run_bindgen(rbconfig, static_ruby, &out_path, None)?,
+    })
+}
+
+/// Writes a file to `$OUT_DIR/versioned_bindings.rs` which includes each of
+/// `bindings` in a module named after its Ruby version, and returns its path.
+/// Include it with `include!(concat!(env!("OUT_DIR"), "/versioned_bindings.rs"))`.
+pub fn write_versioned_modules(bindings: &[VersionedBindings]) -> Result<PathBuf, Box<dyn Error>> {
+    let out_path = PathBuf::from(env::var("OUT_DIR")?).join("versioned_bindings.rs");
+    std::fs::write(&out_path, versioned_modules_source(bindings))?;
 
+    Ok(out_path)
+}
+
+fn versioned_module_name(major: u32, minor: u32) -> String {
+    format!("ruby_{}_{}", major, minor)
+}
+
+fn versioned_modules_source(bindings: &[VersionedBindings]) -> String {
+    let mut source = String::new();
+
+    for versioned in bindings {
+        source.push_str(&format!(
+            "pub mod {} {{\n    include!({:?});\n}}\n",
+            versioned.module_name, versioned.path
+        ));
+    }
+
+    source
+}
+
+/// Runs bindgen and writes the bindings to `out_path`, emitting cargo cfgs
+/// for them when `cfg_out` is given.
+fn run_bindgen(
+    rbconfig: &RbConfig,
+    static_ruby: bool,
+    out_path: &Path,
+    cfg_out: Option<&mut File>,
+) -> Result<PathBuf, Box<dyn Error>> {
     let mut clang_args = vec![];
     if let Some(ruby_include_dir) = rbconfig.get("rubyhdrdir") {
         clang_args.push(format!("-I{}", ruby_include_dir));
@@ -33,6 +121,7 @@ pub fn generate(
     clang_args.extend(Build::default_cflags());
     clang_args.extend(rbconfig.cflags.clone());
     clang_args.extend(rbconfig.cppflags());
+    clang_args.extend(suppressed_warnings());
 
     debug_log!("INFO: using bindgen with clang args: {:?}", clang_args);
 
@@ -44,11 +133,14 @@ pub fn generate(
         wrapper_h.push_str("#endif\n");
     }
 
+    let extra_headers = extra_headers();
+    wrapper_h.push_str(&include_lines(&extra_headers));
+
     if rbconfig.have_ruby_header("ruby/io/buffer.h") {
         clang_args.push("-DHAVE_RUBY_IO_BUFFER_H".to_string());
     }
 
-    let bindings = default_bindgen(clang_args)
+    let bindings = default_bindgen(clang_args.clone(), rust_target()?)
         .allowlist_file(".*ruby.*")
         .blocklist_item("ruby_abi_version")
         .blocklist_function("rb_tr_abi_version")
@@ -57,6 +149,12 @@ pub fn generate(
         .blocklist_function("rb_tr_rdata")
         .parse_callbacks(Box::new(bindgen::CargoCallbacks::new()));
 
+    // Headers outside of Ruby's include dirs are not matched by the
+    // allowlist above.
+    let bindings = extra_headers.iter().fold(bindings, |bindings, header| {
+        bindings.allowlist_file(format!(".*{}", regex::escape(&header.to_string_lossy())))
+    });
+
     let bindings = if cfg!(feature = "bindgen-rbimpls") {
         bindings
     } else {
@@ -73,17 +171,14 @@ pub fn generate(
 
     let bindings = opaqueify_bindings(rbconfig, bindings, &mut wrapper_h);
 
-    let mut tokens = {
+    let (mut tokens, bindgen_version) = {
         write!(std::io::stderr(), "{}", wrapper_h)?;
         let bindings = bindings.header_contents("wrapper.h", &wrapper_h);
         let code_string = bindings.generate()?.to_string();
-        syn::parse_file(&code_string)?
+        let bindgen_version = bindgen_version(&code_string).map(str::to_owned);
+        (syn::parse_file(&code_string)?, bindgen_version)
     };
 
-    let slug = rbconfig.ruby_version_slug();
-    let crate_version = env!("CARGO_PKG_VERSION");
-    let out_path = out_dir.join(format!("bindings-{}-{}.rs", crate_version, slug));
-
     let code = {
         sanitizer::ensure_backwards_compatible_encoding_pointers(&mut tokens);
         clean_docs(rbconfig, &mut tokens);
@@ -92,16 +187,189 @@ pub fn generate(
             qualify_symbols_for_msvc(&mut tokens, static_ruby, rbconfig);
         }
 
-        push_cargo_cfg_from_bindings(&tokens, cfg_out)?;
+        if let Some(cfg_out) = cfg_out {
+            push_cargo_cfg_from_bindings(&tokens.items, cfg_out)?;
+        }
         categorize_bindings(&mut tokens);
         tokens.into_token_stream().to_string()
     };
 
-    let mut out_file = File::create(&out_path)?;
+    let header = provenance_header(rbconfig, bindgen_version.as_deref(), &clang_args);
+    let mut out_file = File::create(out_path)?;
+    std::io::Write::write_all(&mut out_file, header.as_bytes())?;
     std::io::Write::write_all(&mut out_file, code.as_bytes())?;
-    run_rustfmt(&out_path);
+    run_rustfmt(out_path);
 
-    Ok(out_path)
+    Ok(out_path.to_path_buf())
+}
+
+/// Extracts the bindgen version from the comment bindgen puts at the top of
+/// its output (i.e. `/* automatically generated by rust-bindgen 0.69.4 */`).
+fn bindgen_version(code: &str) -> Option<&str> {
+    code.lines()
+        .next()?
+        .trim()
+        .strip_prefix("/* automatically generated by rust-bindgen ")?
+        .strip_suffix("*/")
+        .map(str::trim)
+}
+
+/// A comment block recording how the bindings were generated, so a cached or
+/// committed bindings file can be traced back to the build that produced it.
+fn provenance_header(
+    rbconfig: &RbConfig,
+    bindgen_version: Option<&str>,
+    clang_args: &[String],
+) -> String {
+    let unknown = || "unknown".to_string();
+    let package = match (env::var("CARGO_PKG_NAME"), env::var("CARGO_PKG_VERSION")) {
+        (Ok(name), Ok(version)) => format!("{} {}", name, version),
+        _ => unknown(),
+    };
+    let mut header = String::new();
+
+    header.push_str("// Generated by rb-sys-build, do not edit by hand.\n");
+    header.push_str(&format!("// crate: {}\n", package));
+    header.push_str(&format!("// rb-sys-build: {}\n", env!("CARGO_PKG_VERSION")));
+    header.push_str(&format!(
+        "// bindgen: {}\n",
+        bindgen_version.unwrap_or("unknown")
+    ));
+    header.push_str(&format!("// ruby: {}\n", rbconfig.ruby_version_slug()));
+    header.push_str(&format!(
+        "// target: {}\n",
+        env::var("TARGET").unwrap_or_else(|_| unknown())
+    ));
+    header.push_str("// clang args:\n");
+
+    for arg in clang_args {
+        header.push_str(&format!("//   {}\n", arg));
+    }
+
+    header.push('\n');
+    header
+}
+
+/// The path to a pre-generated bindings file to use instead of running bindgen
+/// (set via `RB_SYS_PREBUILT_BINDINGS`).
+fn prebuilt_bindings_path() -> Option<PathBuf> {
+    println!("cargo:rerun-if-env-changed=RB_SYS_PREBUILT_BINDINGS");
+
+    match env::var_os("RB_SYS_PREBUILT_BINDINGS") {
+        Some(path) if !path.is_empty() => Some(PathBuf::from(path)),
+        _ => None,
+    }
+}
+
+/// Additional headers to include in `wrapper.h`, so their declarations are
+/// bound too (set via `RB_SYS_BINDGEN_EXTRA_HEADERS`, separated like `PATH`).
+fn extra_headers() -> Vec<PathBuf> {
+    println!("cargo:rerun-if-env-changed=RB_SYS_BINDGEN_EXTRA_HEADERS");
+
+    match env::var_os("RB_SYS_BINDGEN_EXTRA_HEADERS") {
+        Some(headers) => env::split_paths(&headers)
+            .filter(|header| !header.as_os_str().is_empty())
+            .collect(),
+        None => vec![],
+    }
+}
+
+/// The `#include` lines for `headers`, which are either relative to the Ruby
+/// include dirs (i.e. `ruby/io/buffer.h`) or absolute.
+fn include_lines(headers: &[PathBuf]) -> String {
+    let mut lines = String::new();
+
+    for header in headers {
+        debug_log!("INFO: including extra header {}", header.display());
+        println!("cargo:rerun-if-changed={}", header.display());
+        lines.push_str(&format!("#include \"{}\"\n", header.display()));
+    }
+
+    lines
+}
+
+// Uses a bindings file that was previously generated by rb-sys verbatim,
+// as long as it was generated for the same Ruby version.
+fn copy_prebuilt_bindings(
+    rbconfig: &RbConfig,
+    src: &Path,
+    out_path: &Path,
+    cfg_out: &mut File,
+) -> Result<PathBuf, Box<dyn Error>> {
+    println!("cargo:rerun-if-changed={}", src.display());
+
+    let code = std::fs::read_to_string(src).map_err(|e| {
+        format!(
+            "could not read prebuilt bindings at {}: {}",
+            src.display(),
+            e
+        )
+    })?;
+    let syntax = syn::parse_file(&code)?;
+    let items = uncategorized_items(&syntax);
+
+    match (prebuilt_ruby_version(items), rbconfig.major_minor()) {
+        (Some(prebuilt), Some(current)) if prebuilt == current => {}
+        (Some((major, minor)), Some((cur_major, cur_minor))) => {
+            return Err(format!(
+                "prebuilt bindings at {} were generated for Ruby {}.{}, but the current Ruby is {}.{}",
+                src.display(),
+                major,
+                minor,
+                cur_major,
+                cur_minor
+            )
+            .into());
+        }
+        _ => {
+            return Err(format!(
+                "could not verify the Ruby version of the prebuilt bindings at {}",
+                src.display()
+            )
+            .into());
+        }
+    }
+
+    debug_log!("INFO: using prebuilt bindings from {}", src.display());
+    push_cargo_cfg_from_bindings(items, cfg_out)?;
+    std::fs::copy(src, out_path)?;
+
+    Ok(out_path.to_path_buf())
+}
+
+// Generated bindings are wrapped in categorized modules, the constants we
+// care about live in `uncategorized`.
+fn uncategorized_items(syntax: &syn::File) -> &[syn::Item] {
+    for item in syntax.items.iter() {
+        if let syn::Item::Mod(m) = item {
+            if m.ident == "uncategorized" {
+                if let Some((_, items)) = &m.content {
+                    return items;
+                }
+            }
+        }
+    }
+
+    &syntax.items
+}
+
+fn prebuilt_ruby_version(items: &[syn::Item]) -> Option<(u32, u32)> {
+    let mut major = None;
+    let mut minor = None;
+
+    for item in items {
+        if let syn::Item::Const(item) = item {
+            let conf = ConfValue::new(item);
+
+            match conf.name().as_str() {
+                "RUBY_API_VERSION_MAJOR" => major = conf.value_string().parse().ok(),
+                "RUBY_API_VERSION_MINOR" => minor = conf.value_string().parse().ok(),
+                _ => {}
+            }
+        }
+    }
+
+    Some((major?, minor?))
 }
 
 fn run_rustfmt(path: &Path) {
@@ -128,8 +396,9 @@ fn clean_docs(rbconfig: &RbConfig, syntax: &mut syn::File) {
     })
 }
 
-fn default_bindgen(clang_args: Vec<String>) -> bindgen::Builder {
+fn default_bindgen(clang_args: Vec<String>, rust_target: RustTarget) -> bindgen::Builder {
     let bindings = bindgen::Builder::default()
+        .rust_target(rust_target)
         .rustified_enum(".*")
         .no_copy("rb_data_type_struct")
         .derive_eq(true)
@@ -144,7 +413,7 @@ fn default_bindgen(clang_args: Vec<String>) -> bindgen::Builder {
         .merge_extern_blocks(true)
         .generate_comments(true)
         .size_t_is_usize(env::var("CARGO_FEATURE_BINDGEN_SIZE_T_IS_USIZE").is_ok())
-        .impl_debug(cfg!(feature = "bindgen-impl-debug"))
+        .impl_debug(impl_debug())
         .parse_callbacks(Box::new(bindgen::CargoCallbacks::new()));
 
     if env::var("CARGO_FEATURE_BINDGEN_ENABLE_FUNCTION_ATTRIBUTE_DETECTION").is_ok() {
@@ -154,6 +423,82 @@ fn default_bindgen(clang_args: Vec<String>) -> bindgen::Builder {
     }
 }
 
+/// The Rust version the generated bindings must compile with, set via the
+/// `RB_SYS_BINDGEN_RUST_TARGET` env var (i.e. `1.65` or `nightly`). Defaults
+/// to the MSRV of `rb-sys`, so the bindings never use newer language features
+/// than `rb-sys` itself.
+fn rust_target() -> Result<RustTarget, Box<dyn Error>> {
+    println!("cargo:rerun-if-env-changed=RB_SYS_BINDGEN_RUST_TARGET");
+
+    match env::var("RB_SYS_BINDGEN_RUST_TARGET") {
+        Ok(version) => parse_rust_target(version.trim()),
+        Err(_) => parse_rust_target(DEFAULT_RUST_TARGET),
+    }
+}
+
+/// Parses a Rust version (i.e. `1.65.0`) into the newest bindgen target which
+/// it supports, since bindgen only knows the versions which added features.
+fn parse_rust_target(version: &str) -> Result<RustTarget, Box<dyn Error>> {
+    if version == "nightly" {
+        return Ok(RustTarget::Nightly);
+    }
+
+    let minor = match version.split('.').collect::<Vec<_>>().as_slice() {
+        ["1", minor] | ["1", minor, _] => minor.parse::<u32>().ok(),
+        _ => None,
+    };
+    let minor = minor.ok_or_else(|| {
+        format!(
+            "invalid RB_SYS_BINDGEN_RUST_TARGET {:?}, expected a Rust version (i.e. \"1.65\") or \"nightly\"",
+            version
+        )
+    })?;
+
+    (0..=minor)
+        .rev()
+        .find_map(|minor| format!("1.{}", minor).parse::<RustTarget>().ok())
+        .ok_or_else(|| format!("bindgen does not support Rust {}", version).into())
+}
+
+/// Clang args to silence warnings from parsing the Ruby headers, set via the
+/// `RB_SYS_BINDGEN_SUPPRESS_WARNINGS` env var: either `1`/`true` to silence
+/// all of them, or a comma-separated list of warning names (i.e.
+/// `deprecated-declarations,unknown-attributes`).
+fn suppressed_warnings() -> Vec<String> {
+    println!("cargo:rerun-if-env-changed=RB_SYS_BINDGEN_SUPPRESS_WARNINGS");
+
+    match env::var("RB_SYS_BINDGEN_SUPPRESS_WARNINGS") {
+        Ok(value) => suppressed_warning_args(&value),
+        Err(_) => vec![],
+    }
+}
+
+fn suppressed_warning_args(value: &str) -> Vec<String> {
+    match value.trim() {
+        "1" | "true" => vec!["-w".to_string()],
+        "" | "0" | "false" => vec![],
+        names => names
+            .split(',')
+            .map(|name| name.trim().trim_start_matches("-W"))
+            .filter(|name| !name.is_empty())
+            .map(|name| format!("-Wno-{}", name.trim_start_matches("no-")))
+            .collect(),
+    }
+}
+
+/// Whether to generate `Debug` impls for structs which can't derive it (i.e.
+/// because they contain unions), via the `bindgen-impl-debug` feature or the
+/// `RB_SYS_BINDGEN_IMPL_DEBUG` env var.
+fn impl_debug() -> bool {
+    println!("cargo:rerun-if-env-changed=RB_SYS_BINDGEN_IMPL_DEBUG");
+
+    cfg!(feature = "bindgen-impl-debug")
+        || matches!(
+            env::var("RB_SYS_BINDGEN_IMPL_DEBUG").as_deref(),
+            Ok("1" | "true")
+        )
+}
+
 // This is needed because bindgen doesn't support the `__declspec(dllimport)` on
 // global variables. Without it, symbols are not found.
 // See https://stackoverflow.com/a/66182704/2057700
@@ -173,7 +518,7 @@ fn qualify_symbols_for_msvc(tokens: &mut syn::File, is_static: bool, rbconfig: &
 
 // Add things like `#[cfg(ruby_use_transient_heap = "true")]` to the bindings config
 fn push_cargo_cfg_from_bindings(
-    syntax: &syn::File,
+    items: &[syn::Item],
     cfg_out: &mut File,
 ) -> Result<(), Box<dyn Error>> {
     fn is_defines(line: &str) -> bool {
@@ -184,7 +529,7 @@ fn push_cargo_cfg_from_bindings(
             || line.starts_with("RUBY_NDEBUG")
     }
 
-    for item in syntax.items.iter() {
+    for item in items.iter() {
         if let syn::Item::Const(item) = item {
             let conf = ConfValue::new(item);
             let conf_name = conf.name();
@@ -252,3 +597,121 @@ impl<'a> ConfValue<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_versioned_modules_source() {
+        let bindings = [
+            VersionedBindings {
+                module_name: versioned_module_name(3, 2),
+                path: PathBuf::from("/out/versioned-bindings-3.2.rs"),
+            },
+            VersionedBindings {
+                module_name: versioned_module_name(3, 3),
+                path: PathBuf::from("/out/versioned-bindings-3.3.rs"),
+            },
+        ];
+
+        assert_eq!(
+            "pub mod ruby_3_2 {\n    include!(\"/out/versioned-bindings-3.2.rs\");\n}\npub mod ruby_3_3 {\n    include!(\"/out/versioned-bindings-3.3.rs\");\n}\n",
+            versioned_modules_source(&bindings)
+        );
+    }
+
+    #[test]
+    fn test_include_lines() {
+        let headers = [
+            PathBuf::from("ruby/fiber/scheduler.h"),
+            PathBuf::from("/opt/my-gem/include/my_gem.h"),
+        ];
+
+        assert_eq!(
+            "#include \"ruby/fiber/scheduler.h\"\n#include \"/opt/my-gem/include/my_gem.h\"\n",
+            include_lines(&headers)
+        );
+        assert_eq!("", include_lines(&[]));
+    }
+
+    #[test]
+    fn test_prebuilt_ruby_version() {
+        let syntax: syn::File = syn::parse_quote! {
+            pub mod uncategorized {
+                pub const RUBY_API_VERSION_MAJOR: u32 = 3;
+                pub const RUBY_API_VERSION_MINOR: u32 = 3;
+                pub const RUBY_API_VERSION_TEENY: u32 = 0;
+            }
+        };
+
+        let items = uncategorized_items(&syntax);
+
+        assert_eq!(prebuilt_ruby_version(items), Some((3, 3)));
+    }
+
+    #[test]
+    fn test_prebuilt_ruby_version_missing() {
+        let syntax: syn::File = syn::parse_quote! {
+            pub mod uncategorized {
+                pub const RUBY_API_VERSION_MAJOR: u32 = 3;
+            }
+        };
+
+        let items = uncategorized_items(&syntax);
+
+        assert_eq!(prebuilt_ruby_version(items), None);
+    }
+
+    #[test]
+    fn test_parse_rust_target() {
+        assert_eq!(parse_rust_target("1.64").unwrap(), RustTarget::Stable_1_64);
+        assert_eq!(parse_rust_target("1.65").unwrap(), RustTarget::Stable_1_64);
+        assert_eq!(
+            parse_rust_target("1.72.1").unwrap(),
+            RustTarget::Stable_1_71
+        );
+        assert_eq!(parse_rust_target("nightly").unwrap(), RustTarget::Nightly);
+        assert!(parse_rust_target("2.0").is_err());
+        assert!(parse_rust_target("latest").is_err());
+    }
+
+    #[test]
+    fn test_suppressed_warning_args() {
+        assert_eq!(suppressed_warning_args("true"), ["-w"]);
+        assert_eq!(suppressed_warning_args("1"), ["-w"]);
+        assert!(suppressed_warning_args("false").is_empty());
+        assert!(suppressed_warning_args("").is_empty());
+        assert_eq!(
+            suppressed_warning_args("deprecated-declarations, -Wunknown-attributes,,"),
+            ["-Wno-deprecated-declarations", "-Wno-unknown-attributes"]
+        );
+    }
+
+    #[test]
+    fn test_bindgen_version() {
+        let code =
+            "/* automatically generated by rust-bindgen 0.69.4 */\n\npub const FOO: u32 = 1;";
+
+        assert_eq!(bindgen_version(code), Some("0.69.4"));
+        assert_eq!(bindgen_version("pub const FOO: u32 = 1;"), None);
+    }
+
+    #[test]
+    fn test_provenance_header() {
+        let mut rbconfig = RbConfig::new();
+        rbconfig.set_value_for_key("RUBY_PROGRAM_VERSION", "3.3.0".into());
+        rbconfig.set_value_for_key("arch", "x86_64-linux".into());
+
+        let clang_args = vec!["-I/opt/ruby/include".to_string(), "-fdeclspec".to_string()];
+        let header = provenance_header(&rbconfig, Some("0.69.4"), &clang_args);
+
+        assert!(header.contains("// bindgen: 0.69.4\n"));
+        assert!(header.contains(&format!("// ruby: {}\n", rbconfig.ruby_version_slug())));
+        assert!(header.contains("//   -I/opt/ruby/include\n//   -fdeclspec\n"));
+        assert!(header
+            .lines()
+            .all(|line| line.is_empty() || line.starts_with("// ")));
+        assert!(syn::parse_file(&header).is_ok());
+    }
+}
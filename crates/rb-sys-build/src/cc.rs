@@ -20,6 +20,8 @@ const WELL_KNOWN_WRAPPERS: &[&str] = &["sccache", "cachepot"];
 pub struct Build {
     files: Vec<PathBuf>,
     flags: Vec<String>,
+    compiler_env: Option<String>,
+    cache_dir: Option<PathBuf>,
 }
 
 impl Build {
@@ -44,13 +46,48 @@ impl Build {
         self.files.push(file);
     }
 
+    /// Prefer the compiler from the given env var (i.e. `RB_SYS_SHIM_CC`) over
+    /// `CC`, for this build only. If the env var is unset or empty, the usual
+    /// `CC` env var or `RbConfig::CONFIG["CC"]` is used instead.
+    pub fn compiler_env(&mut self, env_var: &str) -> &mut Self {
+        self.compiler_env = Some(env_var.to_owned());
+        self
+    }
+
+    /// Reuse the compiled archive from `dir` if an identical build (same
+    /// sources, compiler, flags, target and Ruby) was already done, and store
+    /// it there otherwise. Sharing a directory between crates (i.e. in the
+    /// workspace target dir) avoids compiling the same C code repeatedly.
+    pub fn cache_dir(&mut self, dir: PathBuf) -> &mut Self {
+        self.cache_dir = Some(dir);
+        self
+    }
+
     pub fn try_compile(self, name: &str) -> Result<()> {
-        let compiler = get_compiler();
+        let compiler = get_compiler(self.compiler_env.as_deref());
         let archiver = get_archiver();
         let out_dir = PathBuf::from(env::var("OUT_DIR")?).join("cc");
         fs::create_dir_all(&out_dir)?;
         let rb = rb_config();
 
+        let cached_lib = match &self.cache_dir {
+            Some(dir) => {
+                let key = self.cache_key(&compiler, &rb)?;
+                Some(dir.join(format!("lib{}-{}.a", name, key)))
+            }
+            None => None,
+        };
+
+        if let Some(cached_lib) = cached_lib.as_deref().filter(|path| path.is_file()) {
+            debug_log!("INFO: using cached archive {}", cached_lib.display());
+            let lib_name = restore_cached_archive(cached_lib, &out_dir)?;
+
+            println!("cargo:rustc-link-search=native={}", out_dir.display());
+            println!("cargo:rustc-link-lib=static={}", lib_name);
+
+            return Ok(());
+        }
+
         let object_files = self.compile_each_file(compiler, &rb, &out_dir)?;
         debug_log!("INFO: compiled object files: {:?}", object_files);
         let (lib_path, lib_name) =
@@ -59,12 +96,48 @@ impl Build {
             debug_log!("WARN: failed to strip archived objects: {:?}", e);
         }
 
+        if let Some(cached_lib) = cached_lib {
+            if let Err(e) = store_cached_archive(&lib_path, &cached_lib) {
+                debug_log!("WARN: failed to cache archive: {:?}", e);
+            }
+        }
+
         println!("cargo:rustc-link-search=native={}", out_dir.display());
         println!("cargo:rustc-link-lib=static={}", lib_name);
 
         Ok(())
     }
 
+    /// A key which changes whenever anything that affects the compiled
+    /// archive changes.
+    fn cache_key(&self, compiler: &Command, rb: &rb_config::RbConfig) -> Result<u64> {
+        let mut hasher = DefaultHasher::new();
+        let mut write_str = |s: &str| {
+            hasher.write(s.as_bytes());
+            hasher.write_u8(0);
+        };
+
+        write_str(env!("CARGO_PKG_VERSION"));
+        write_str(&env::var("TARGET").unwrap_or_default());
+        write_str(&rb.ruby_version_slug());
+        write_str(&format!("{:?}", compiler));
+
+        for arg in get_include_args(rb)
+            .iter()
+            .chain(&rb.cflags)
+            .chain(&get_common_args())
+            .chain(&self.flags)
+        {
+            write_str(arg);
+        }
+
+        for file in &self.files {
+            hasher.write(&fs::read(file)?);
+        }
+
+        Ok(hasher.finish())
+    }
+
     fn compile_each_file(
         &self,
         compiler: Command,
@@ -169,6 +242,47 @@ impl Build {
     }
 }
 
+/// Copies a cached archive into `out_dir`, returning the name to link it by.
+fn restore_cached_archive(cached_lib: &Path, out_dir: &Path) -> Result<String> {
+    let file_name = cached_lib
+        .file_name()
+        .ok_or("invalid cached archive path")?;
+    let lib_name = file_name
+        .to_str()
+        .and_then(|f| f.strip_prefix("lib"))
+        .and_then(|f| f.strip_suffix(".a"))
+        .ok_or("invalid cached archive path")?
+        .to_owned();
+    let dst = out_dir.join(file_name);
+
+    fs::copy(cached_lib, &dst)?;
+
+    // See `archive_object_files` for why MSVC needs both.
+    if is_msvc() {
+        fs::copy(cached_lib, dst.with_file_name(format!("{}.lib", lib_name)))?;
+    }
+
+    Ok(lib_name)
+}
+
+/// Stores an archive in the cache. The archive is written to a temporary file
+/// first, so concurrent builds never see a partially written one.
+fn store_cached_archive(lib_path: &Path, cached_lib: &Path) -> Result<()> {
+    if let Some(dir) = cached_lib.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let tmp = cached_lib.with_extension(format!("tmp{}", std::process::id()));
+    fs::copy(lib_path, &tmp)?;
+
+    if let Err(e) = fs::rename(&tmp, cached_lib) {
+        let _ = fs::remove_file(&tmp);
+        return Err(e.into());
+    }
+
+    Ok(())
+}
+
 fn get_include_args(rb: &rb_config::RbConfig) -> Vec<String> {
     let mut args = vec![];
     if let Some(include_dir) = rb.get("rubyhdrdir") {
@@ -235,8 +349,11 @@ fn get_common_args() -> Vec<String> {
     items
 }
 
-fn get_compiler() -> Command {
-    let cmd = get_tool("CC", "cc");
+fn get_compiler(compiler_env: Option<&str>) -> Command {
+    let cc_env = compiler_env
+        .filter(|env_var| get_tool_from_env(env_var).map_or(false, |cc| !cc.is_empty()))
+        .unwrap_or("CC");
+    let cmd = get_tool(cc_env, "cc");
     let cmd_program = cmd.get_program().to_str().unwrap_or_default();
     let already_wrapped = WELL_KNOWN_WRAPPERS.iter().any(|w| cmd_program.contains(w));
 
@@ -1,6 +1,7 @@
 use std::{
     collections::{hash_map::Keys, HashMap},
     env,
+    error::Error,
     path::PathBuf,
     process::Command,
 };
@@ -32,6 +33,7 @@ pub struct RbConfig {
     pub blocklist_lib: Vec<String>,
     pub blocklist_link_arg: Vec<String>,
     use_rpath: bool,
+    rpath: Option<String>,
     value_map: HashMap<String, String>,
 }
 
@@ -53,6 +55,7 @@ impl RbConfig {
             cflags: Vec::new(),
             value_map: HashMap::new(),
             use_rpath: false,
+            rpath: None,
         }
     }
 
@@ -64,6 +67,7 @@ impl RbConfig {
     /// Instantiates a new `RbConfig` for the current Ruby.
     pub fn current() -> RbConfig {
         println!("cargo:rerun-if-env-changed=RUBY");
+        println!("cargo:rerun-if-env-changed=RUBY_PKG_CONFIG_NAME");
 
         let mut rbconfig = RbConfig::new();
 
@@ -72,22 +76,43 @@ impl RbConfig {
         let parsed = if rbconfig.is_cross_compiling() {
             HashMap::new()
         } else {
-            let output = memoize!(String: {
+            let output = memoize!(Result<String, String>: {
                 let ruby = env::var_os("RUBY").unwrap_or_else(|| OsString::from("ruby"));
 
-                let config = Command::new(ruby)
+                match Command::new(ruby)
                     .arg("--disable-gems")
                     .arg("-rrbconfig")
                     .arg("-e")
                     .arg("print RbConfig::CONFIG.map {|kv| kv.join(\"\x1F\")}.join(\"\x1E\")")
                     .output()
-                    .unwrap_or_else(|e| panic!("ruby not found: {}", e));
-                if !config.status.success() {
-                    panic!("non-zero exit status while dumping RbConfig: {:?}", config);
+                {
+                    Ok(config) => {
+                        if !config.status.success() {
+                            panic!("non-zero exit status while dumping RbConfig: {:?}", config);
+                        }
+                        Ok(String::from_utf8(config.stdout).expect("RbConfig value not UTF-8!"))
+                    }
+                    Err(e) => Err(format!("ruby not found: {}", e)),
                 }
-                String::from_utf8(config.stdout).expect("RbConfig value not UTF-8!")
             });
 
+            let output = match output {
+                Ok(output) => output,
+                Err(ruby_err) => {
+                    let name = env::var("RUBY_PKG_CONFIG_NAME").unwrap_or_else(|_| "ruby".into());
+
+                    match RbConfig::from_pkg_config(&name) {
+                        Ok(rbconfig) => {
+                            debug_log!("INFO: {}, using pkg-config ({}) instead", ruby_err, name);
+                            return rbconfig;
+                        }
+                        Err(pc_err) => {
+                            panic!("{} (pkg-config fallback failed: {})", ruby_err, pc_err)
+                        }
+                    }
+                }
+            };
+
             let mut parsed = HashMap::new();
             for line in output.split('\x1E') {
                 let mut parts = line.splitn(2, '\x1F');
@@ -106,12 +131,92 @@ impl RbConfig {
         rbconfig
     }
 
+    /// Instantiates a new `RbConfig` from a Ruby pkg-config file (i.e.
+    /// `ruby-3.3`), without invoking the `ruby` binary.
+    ///
+    /// Only the keys that Ruby writes to its `.pc` file are available, which
+    /// covers what is needed to generate bindings and link libruby. The
+    /// `pkg-config` binary can be overridden with the `PKG_CONFIG` environment
+    /// variable.
+    pub fn from_pkg_config(name: &str) -> Result<RbConfig, Box<dyn Error>> {
+        println!("cargo:rerun-if-env-changed=PKG_CONFIG");
+        println!("cargo:rerun-if-env-changed=PKG_CONFIG_PATH");
+
+        let pkg_config = env::var_os("PKG_CONFIG").unwrap_or_else(|| OsString::from("pkg-config"));
+        let run = |args: &[&str]| -> Result<String, Box<dyn Error>> {
+            let output = Command::new(&pkg_config).args(args).arg(name).output()?;
+
+            if !output.status.success() {
+                return Err(format!(
+                    "pkg-config {} {} failed: {}",
+                    args.join(" "),
+                    name,
+                    String::from_utf8_lossy(&output.stderr).trim()
+                )
+                .into());
+            }
+
+            Ok(String::from_utf8(output.stdout)?.trim().to_owned())
+        };
+
+        run(&["--exists"])?;
+
+        let mut rbconfig = RbConfig::new();
+
+        for key in PKG_CONFIG_KEYS {
+            let value = run(&[&format!("--variable={}", key)])?;
+
+            if !value.is_empty() {
+                rbconfig.set_value_for_key(key, value);
+            }
+        }
+
+        if rbconfig.major_minor().is_none() {
+            return Err(format!("pkg-config file for {} does not define MAJOR/MINOR", name).into());
+        }
+
+        if let Some(install_name) = rbconfig.get("RUBY_INSTALL_NAME") {
+            rbconfig.set_value_for_key("ruby_install_name", install_name);
+        }
+
+        let cflags = run(&["--cflags"])?;
+        rbconfig.push_cflags(&cflags);
+        rbconfig.set_value_for_key("cflags", cflags);
+
+        if let Some(dldflags) = rbconfig.get("DLDFLAGS") {
+            rbconfig.push_dldflags(&dldflags);
+        }
+
+        Ok(rbconfig)
+    }
+
     /// Pushes the `LIBRUBYARG` flags so Ruby will be linked.
     pub fn link_ruby(&mut self, is_static: bool) -> &mut Self {
         let Some(libdir) = self.get("libdir") else {
             return self;
         };
 
+        if let Some(missing) = self.missing_libruby(is_static) {
+            let (kind, hint) = if is_static {
+                (
+                    "statically",
+                    "Ruby may have been built with `--enable-shared` only. Disable the `ruby-static` feature (and unset `RUBY_STATIC`) to link against the shared libruby instead.",
+                )
+            } else {
+                (
+                    "dynamically",
+                    "Ruby may have been built without `--enable-shared`. Enable the `ruby-static` feature (or set `RUBY_STATIC=1`) to link libruby statically instead.",
+                )
+            };
+
+            panic!(
+                "cannot link Ruby {}, {} does not exist. {}",
+                kind,
+                missing.display(),
+                hint
+            );
+        }
+
         self.push_search_path(libdir.as_str());
         self.push_dldflags(&format!("-L{}", libdir));
 
@@ -158,6 +263,31 @@ impl RbConfig {
         self
     }
 
+    /// Returns the path of the libruby which would be linked (`LIBRUBY_A` when
+    /// static, `LIBRUBY` otherwise) if it is missing from an existing `libdir`.
+    fn missing_libruby(&self, is_static: bool) -> Option<PathBuf> {
+        let libdir = PathBuf::from(self.get("libdir")?);
+        let name = if is_static {
+            self.get("LIBRUBY_A")?
+        } else {
+            self.get("LIBRUBY")?
+        };
+
+        // A missing `libdir` is left for the linker to report, since the
+        // libraries may be found on another search path.
+        if name.is_empty() || !libdir.is_dir() {
+            return None;
+        }
+
+        let path = libdir.join(name);
+
+        if path.exists() {
+            None
+        } else {
+            Some(path)
+        }
+    }
+
     /// Get the name for libruby-static (i.e. `ruby.3.1-static`).
     pub fn libruby_static_name(&self) -> String {
         let Some(lib) = self.get("LIBRUBY_A") else {
@@ -223,6 +353,25 @@ impl RbConfig {
         }
     }
 
+    /// Returns true if the current Ruby is a debug build, i.e. it was compiled
+    /// without optimizations (`optflags` of `-O0` or `-Og`) or with
+    /// `RUBY_DEBUG` enabled.
+    pub fn is_debug_build(&self) -> bool {
+        let optflags = self.get("optflags").unwrap_or_default();
+        let unoptimized = shellsplit(optflags)
+            .iter()
+            .any(|flag| matches!(flag.as_str(), "-O0" | "-Og" | "/Od"));
+
+        let ruby_debug = self.cppflags().iter().chain(&self.cflags).any(|flag| {
+            flag == "-DRUBY_DEBUG"
+                || flag
+                    .strip_prefix("-DRUBY_DEBUG=")
+                    .map_or(false, |val| val != "0")
+        });
+
+        unoptimized || ruby_debug
+    }
+
     /// Returns true if the current Ruby is cross compiling.
     pub fn is_cross_compiling(&self) -> bool {
         if let Some(cross) = self.get("CROSS_COMPILING") {
@@ -245,10 +394,34 @@ impl RbConfig {
         self
     }
 
-    /// Push cflags string
+    /// Sets the rpath to embed, instead of the directories of the linked
+    /// libraries. It is passed to the linker verbatim, so it can be relative
+    /// to the extension (i.e. `$ORIGIN/../lib` on Linux, or
+    /// `@loader_path/../lib` on macOS) for a Ruby which may be relocated.
+    pub fn rpath(&mut self, rpath: &str) -> &mut RbConfig {
+        self.rpath = Some(rpath.to_owned());
+        self
+    }
+
+    /// Push cflags string. When targeting Apple, `-arch` flags for other
+    /// architectures (i.e. from a fat binary Ruby) are skipped.
     pub fn push_cflags(&mut self, cflags: &str) -> &mut Self {
-        for flag in shellsplit(cflags) {
-            if !self.cflags.contains(&flag) {
+        let target = env::var("TARGET").ok();
+        let mut flags = shellsplit(cflags).into_iter();
+
+        while let Some(flag) = flags.next() {
+            if flag == "-arch" {
+                let Some(arch) = flags.next() else {
+                    continue;
+                };
+
+                if is_foreign_arch(&arch, target.as_deref()) {
+                    warn_foreign_arch("cflag", &arch, target.as_deref());
+                } else if !self.cflags.windows(2).any(|w| w[0] == flag && w[1] == arch) {
+                    self.cflags.push(flag);
+                    self.cflags.push(arch);
+                }
+            } else if !self.cflags.contains(&flag) {
                 self.cflags.push(flag.to_string());
             }
         }
@@ -279,11 +452,15 @@ impl RbConfig {
                 result.push(format!("cargo:rustc-link-lib={}", lib));
             }
 
-            if self.use_rpath && !lib.is_static() {
+            if self.use_rpath && self.rpath.is_none() && !lib.is_static() {
                 result.push(format!("cargo:rustc-link-arg=-Wl,-rpath,{}", lib));
             }
         }
 
+        if let Some(rpath) = &self.rpath {
+            result.push(format!("cargo:rustc-link-arg=-Wl,-rpath,{}", rpath));
+        }
+
         for link_arg in &self.link_args {
             if !self.blocklist_link_arg.iter().any(|b| link_arg == b) {
                 result.push(format!("cargo:rustc-link-arg={}", link_arg));
@@ -320,6 +497,8 @@ impl RbConfig {
         let dynamic_lib_regex = Regex::new(r"^-l\s*:lib(?P<name>\S+).(so|dylib|dll)$").unwrap();
         let framework_regex_short = Regex::new(r"^-F\s*(?P<name>.*)$").unwrap();
         let framework_regex_long = Regex::new(r"^-framework\s*(?P<name>.*)$").unwrap();
+        let arch_regex = Regex::new(r"^-arch\s+(?P<name>\S+)$").unwrap();
+        let target = env::var("TARGET").ok();
 
         for arg in split_args {
             let arg = arg.trim().to_owned();
@@ -340,6 +519,10 @@ impl RbConfig {
                 self.push_search_path((SearchPathKind::Framework, name));
             } else if let Some(name) = capture_name(&framework_regex_long, &arg) {
                 self.push_library((LibraryKind::Framework, name));
+            } else if let Some(name) = capture_name(&arch_regex, &arg)
+                .filter(|arch| is_foreign_arch(arch, target.as_deref()))
+            {
+                warn_foreign_arch("link arg", &name, target.as_deref());
             } else {
                 self.push_link_arg(arg);
             }
@@ -476,6 +659,33 @@ impl RbConfig {
     }
 }
 
+/// The `RbConfig` keys that Ruby writes to its pkg-config file.
+const PKG_CONFIG_KEYS: &[&str] = &[
+    "MAJOR",
+    "MINOR",
+    "TEENY",
+    "ruby_version",
+    "RUBY_API_VERSION",
+    "RUBY_PROGRAM_VERSION",
+    "RUBY_BASE_NAME",
+    "RUBY_INSTALL_NAME",
+    "RUBY_SO_NAME",
+    "arch",
+    "prefix",
+    "exec_prefix",
+    "libdir",
+    "includedir",
+    "rubyhdrdir",
+    "rubyarchhdrdir",
+    "LIBRUBY",
+    "LIBRUBY_A",
+    "LIBRUBYARG",
+    "LIBRUBYARG_SHARED",
+    "LIBRUBYARG_STATIC",
+    "LIBS",
+    "DLDFLAGS",
+];
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum RubyEngine {
     Mri,
@@ -499,6 +709,33 @@ fn capture_name(regex: &Regex, arg: &str) -> Option<String> {
         .map(|cap| cap.name("name").unwrap().as_str().trim().to_owned())
 }
 
+/// Warns that an `-arch` flag of `kind` (cflag or link arg) was skipped.
+fn warn_foreign_arch(kind: &str, arch: &str, target: Option<&str>) {
+    println!(
+        "cargo:warning=Skipping {} \"-arch {}\" from RbConfig, which does not match the target {}",
+        kind,
+        arch,
+        target.unwrap_or("(unknown)")
+    );
+}
+
+/// Checks if an `-arch` value (i.e. `x86_64` or `arm64`) does not match the
+/// Apple target being built for. Always false for non-Apple targets.
+fn is_foreign_arch(arch: &str, target: Option<&str>) -> bool {
+    let Some(target) = target.filter(|t| t.contains("-apple-")) else {
+        return false;
+    };
+
+    let target_arch = match target.split('-').next() {
+        Some("aarch64") => "arm64",
+        Some("i686") => "i386",
+        Some(other) => other,
+        None => return false,
+    };
+
+    arch != target_arch
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -753,6 +990,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_rpath_override() {
+        let mut rb_config = RbConfig::new();
+        rb_config.push_dldflags("-lfoo -lbar");
+        rb_config.use_rpath();
+        rb_config.rpath("$ORIGIN/../lib");
+
+        assert_eq!(
+            vec![
+                "cargo:rustc-link-lib=foo",
+                "cargo:rustc-link-lib=bar",
+                "cargo:rustc-link-arg=-Wl,-rpath,$ORIGIN/../lib"
+            ],
+            rb_config.cargo_args()
+        );
+    }
+
     #[test]
     fn test_link_mswin() {
         with_locked_env(|| {
@@ -782,6 +1036,39 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_missing_libruby() {
+        let libdir = env::temp_dir().join(format!("rb-sys-libdir-{}", std::process::id()));
+        std::fs::create_dir_all(&libdir).unwrap();
+        std::fs::write(libdir.join("libruby.so.3.3.0"), "").unwrap();
+
+        let mut rb_config = RbConfig::new();
+        rb_config.set_value_for_key("libdir", libdir.to_string_lossy().into_owned());
+        rb_config.set_value_for_key("LIBRUBY", "libruby.so.3.3.0".into());
+        rb_config.set_value_for_key("LIBRUBY_A", "libruby-static.a".into());
+
+        assert_eq!(None, rb_config.missing_libruby(false));
+        assert_eq!(
+            Some(libdir.join("libruby-static.a")),
+            rb_config.missing_libruby(true)
+        );
+
+        std::fs::write(libdir.join("libruby-static.a"), "").unwrap();
+        assert_eq!(None, rb_config.missing_libruby(true));
+
+        std::fs::remove_dir_all(&libdir).unwrap();
+    }
+
+    #[test]
+    fn test_missing_libruby_without_libdir() {
+        let mut rb_config = RbConfig::new();
+        rb_config.set_value_for_key("libdir", "/nonexistent/rb-sys/lib".into());
+        rb_config.set_value_for_key("LIBRUBY_A", "libruby-static.a".into());
+
+        assert_eq!(None, rb_config.missing_libruby(true));
+        assert_eq!(None, rb_config.missing_libruby(false));
+    }
+
     #[test]
     fn test_link_static() {
         with_locked_env(|| {
@@ -835,6 +1122,61 @@ mod tests {
         });
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_from_pkg_config() {
+        use std::os::unix::fs::PermissionsExt;
+
+        with_locked_env(|| {
+            let script =
+                env::temp_dir().join(format!("rb-sys-fake-pkg-config-{}", std::process::id()));
+            std::fs::write(
+                &script,
+                r#"#!/bin/sh
+case "$*" in
+  "--exists ruby-3.3") ;;
+  "--variable=MAJOR ruby-3.3") echo 3 ;;
+  "--variable=MINOR ruby-3.3") echo 3 ;;
+  "--variable=TEENY ruby-3.3") echo 0 ;;
+  "--variable=RUBY_INSTALL_NAME ruby-3.3") echo ruby ;;
+  "--variable=arch ruby-3.3") echo x86_64-linux ;;
+  "--variable=rubyhdrdir ruby-3.3") echo /opt/ruby/include/ruby-3.3.0 ;;
+  "--variable=DLDFLAGS ruby-3.3") echo "-L/opt/ruby/lib -Wl,--compress-debug-sections=zlib" ;;
+  "--cflags ruby-3.3") echo "-I/opt/ruby/include/ruby-3.3.0" ;;
+  --variable=*) echo ;;
+  *) exit 1 ;;
+esac
+"#,
+            )
+            .unwrap();
+            std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+            env::set_var("PKG_CONFIG", &script);
+
+            let rb_config = RbConfig::from_pkg_config("ruby-3.3").unwrap();
+            let missing = RbConfig::from_pkg_config("ruby-2.7");
+
+            env::remove_var("PKG_CONFIG");
+            std::fs::remove_file(&script).unwrap();
+
+            assert_eq!(rb_config.major_minor(), Some((3, 3)));
+            assert_eq!(rb_config.ruby_version_slug(), "mri-x86_64-linux-3.3.0");
+            assert_eq!(
+                rb_config.get("rubyhdrdir"),
+                Some("/opt/ruby/include/ruby-3.3.0".into())
+            );
+            assert_eq!(rb_config.get("RUBY_SO_NAME"), None);
+            assert_eq!(rb_config.cflags, vec!["-I/opt/ruby/include/ruby-3.3.0"]);
+            assert_eq!(
+                rb_config.search_paths,
+                vec![SearchPath {
+                    kind: SearchPathKind::Native,
+                    name: "/opt/ruby/lib".into()
+                }]
+            );
+            assert!(missing.is_err());
+        });
+    }
+
     #[test]
     fn test_libstatic() {
         let mut rb_config = RbConfig::new();
@@ -843,6 +1185,69 @@ mod tests {
         assert_eq!(rb_config.link_args, ["-l:libssp.a".to_string()]);
     }
 
+    #[test]
+    fn test_is_foreign_arch() {
+        assert!(is_foreign_arch("x86_64", Some("aarch64-apple-darwin")));
+        assert!(is_foreign_arch("arm64", Some("x86_64-apple-darwin")));
+        assert!(!is_foreign_arch("arm64", Some("aarch64-apple-darwin")));
+        assert!(!is_foreign_arch("x86_64", Some("x86_64-apple-darwin")));
+        assert!(!is_foreign_arch(
+            "x86_64",
+            Some("aarch64-unknown-linux-gnu")
+        ));
+        assert!(!is_foreign_arch("x86_64", None));
+    }
+
+    #[test]
+    fn test_cflags_fat_binary_arch() {
+        with_locked_env(|| {
+            let old_var = env::var("TARGET").ok();
+            env::set_var("TARGET", "aarch64-apple-darwin");
+
+            let mut rb_config = RbConfig::new();
+            rb_config.push_cflags("-O3 -arch x86_64 -arch arm64 -fno-common");
+            rb_config.push_dldflags("-L/opt/ruby/lib -arch x86_64 -lruby");
+
+            if let Some(old_var) = old_var {
+                env::set_var("TARGET", old_var);
+            } else {
+                env::remove_var("TARGET");
+            }
+
+            assert_eq!(rb_config.cflags, ["-O3", "-arch", "arm64", "-fno-common"]);
+            assert!(rb_config.link_args.is_empty());
+        });
+    }
+
+    #[test]
+    fn test_cflags_arch_without_target() {
+        with_locked_env(|| {
+            let mut rb_config = RbConfig::new();
+            rb_config.push_cflags("-arch x86_64 -arch arm64");
+
+            assert_eq!(rb_config.cflags, ["-arch", "x86_64", "-arch", "arm64"]);
+        });
+    }
+
+    #[test]
+    fn test_is_debug_build() {
+        let mut rb_config = RbConfig::new();
+        rb_config.set_value_for_key("optflags", "-O3 -fno-fast-math".into());
+        rb_config.set_value_for_key("debugflags", "-ggdb3".into());
+        assert!(!rb_config.is_debug_build());
+
+        rb_config.set_value_for_key("optflags", "-O0".into());
+        assert!(rb_config.is_debug_build());
+
+        let mut rb_config = RbConfig::new();
+        rb_config.set_value_for_key("optflags", "-O3".into());
+        rb_config.push_cflags("-DRUBY_DEBUG=0");
+        assert!(!rb_config.is_debug_build());
+
+        rb_config.push_cflags("-DRUBY_DEBUG=1");
+        assert!(rb_config.is_debug_build());
+    }
+
     #[test]
     fn test_link_arg_blocklist() {
         let mut rb_config = RbConfig::new();
@@ -6,6 +6,7 @@ mod utils;
 mod defines;
 mod rb_env;
 mod ruby_version;
+mod symbols;
 
 use std::error::Error;
 
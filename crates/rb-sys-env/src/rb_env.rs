@@ -1,5 +1,10 @@
-use crate::{Defines, RubyVersion};
-use std::{collections::HashMap, rc::Rc};
+use crate::{symbols, Defines, RubyVersion};
+use std::{
+    collections::{HashMap, HashSet},
+    io,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
 
 const ENV_PREFIX: &str = "DEP_RB_";
 const RBCONFIG_PREFIX: &str = "RBCONFIG_";
@@ -23,6 +28,24 @@ impl RbEnv {
         self.ruby_version().major_minor()
     }
 
+    /// The ABI version (i.e. `(3, 3)`) of the Ruby that `rb-sys` was built
+    /// against, taken from `RbConfig::CONFIG["RUBY_API_VERSION"]`.
+    ///
+    /// An extension can only be loaded by a Ruby with the same ABI version, which
+    /// can be checked at load time with `rb_sys::check_abi()`.
+    pub fn abi_version(&self) -> (u8, u8) {
+        let parsed = self.get_rbconfig_value("RUBY_API_VERSION").and_then(|v| {
+            let mut parts = v.split('.').map(|p| p.parse::<u8>().ok());
+
+            match (parts.next(), parts.next()) {
+                (Some(Some(major)), Some(Some(minor))) => Some((major, minor)),
+                _ => None,
+            }
+        });
+
+        parsed.unwrap_or_else(|| self.ruby_major_minor())
+    }
+
     /// Get a value from the current Ruby's `RbConfig::CONFIG`.
     pub fn get_rbconfig_value(&self, key: &str) -> Option<&str> {
         self.vars
@@ -30,6 +53,25 @@ impl RbEnv {
             .map(|v| v.as_str())
     }
 
+    /// The directory for architecture-specific files installed by the site
+    /// (i.e. with `gem install`), from `RbConfig::CONFIG["sitearchdir"]`.
+    pub fn sitearch_dir(&self) -> Option<PathBuf> {
+        self.get_rbconfig_dir("sitearchdir")
+    }
+
+    /// The directory for architecture-specific files installed by the vendor
+    /// (i.e. an OS package), from `RbConfig::CONFIG["vendorarchdir"]`. This is
+    /// `None` if Ruby was configured without a vendor directory.
+    pub fn vendorarch_dir(&self) -> Option<PathBuf> {
+        self.get_rbconfig_dir("vendorarchdir")
+    }
+
+    fn get_rbconfig_dir(&self, key: &str) -> Option<PathBuf> {
+        self.get_rbconfig_value(key)
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+    }
+
     /// List the Cargo features of rb-sys
     pub fn cargo_features(&self) -> Vec<String> {
         let keys = self.vars.keys();
@@ -69,6 +111,165 @@ impl RbEnv {
             .unwrap_or(false)
     }
 
+    /// Writes a Rust source file with constants describing the Ruby the crate
+    /// is built against (`RUBY_VERSION`, `RUBY_API_VERSION`, `PLATFORM`,
+    /// `ENGINE` and `RUBY_STATIC`), to be `include!`d by the crate.
+    ///
+    /// ```no_run
+    /// // In your crate's build.rs
+    /// pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let rb_env = rb_sys_env::activate()?;
+    ///     let out_dir = std::path::PathBuf::from(std::env::var("OUT_DIR")?);
+    ///
+    ///     rb_env.write_build_info(out_dir.join("build_info.rs"))?;
+    ///
+    ///     Ok(())
+    /// }
+    ///
+    /// // In your crate's lib.rs
+    /// pub mod build_info {
+    ///     include!(concat!(env!("OUT_DIR"), "/build_info.rs"));
+    /// }
+    /// ```
+    pub fn write_build_info<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        std::fs::write(path, self.build_info_source())
+    }
+
+    fn build_info_source(&self) -> String {
+        let (major, minor, teeny) = self.ruby_version().major_minor_teeny();
+        let (abi_major, abi_minor) = self.abi_version();
+        let platform = self
+            .get_rbconfig_value("platform")
+            .or_else(|| self.get_rbconfig_value("arch"))
+            .unwrap_or("unknown");
+        let engine = self.ruby_engine_name();
+
+        let mut source = String::from("// Generated by rb-sys-env, do not edit by hand.\n\n");
+        let mut push_const = |doc: &str, name: &str, ty: &str, value: String| {
+            source.push_str(&format!("/// {}\n", doc));
+            source.push_str(&format!("pub const {}: {} = {};\n", name, ty, value));
+        };
+
+        push_const(
+            "The version of Ruby the crate was built against (i.e. `\"3.3.0\"`).",
+            "RUBY_VERSION",
+            "&str",
+            format!("\"{}.{}.{}\"", major, minor, teeny),
+        );
+        push_const(
+            "The ABI version of Ruby the crate was built against (i.e. `\"3.3\"`).",
+            "RUBY_API_VERSION",
+            "&str",
+            format!("\"{}.{}\"", abi_major, abi_minor),
+        );
+        push_const(
+            "The Ruby platform (i.e. `\"x86_64-linux\"`).",
+            "PLATFORM",
+            "&str",
+            format!("{:?}", platform),
+        );
+        push_const(
+            "The Ruby engine (i.e. `\"ruby\"` or `\"truffleruby\"`).",
+            "ENGINE",
+            "&str",
+            format!("{:?}", engine),
+        );
+        push_const(
+            "Whether libruby is linked statically.",
+            "RUBY_STATIC",
+            "bool",
+            self.is_ruby_static().to_string(),
+        );
+
+        source
+    }
+
+    /// The `RUBY_ENGINE` of the Ruby (i.e. `"ruby"` for MRI), from the engine
+    /// rb-sys detected. Falls back to `ruby_install_name`, which may have a
+    /// program suffix (i.e. `ruby3.3`), so unknown names are treated as MRI
+    /// like rb-sys does.
+    fn ruby_engine_name(&self) -> &str {
+        let engine = self
+            .vars
+            .get("ENGINE")
+            .map(|v| v.as_str())
+            .or_else(|| self.get_rbconfig_value("ruby_install_name"));
+
+        match engine {
+            Some("truffleruby") => "truffleruby",
+            Some("jruby") => "jruby",
+            _ => "ruby",
+        }
+    }
+
+    /// Finds libruby in `RbConfig::CONFIG["libdir"]` (or `bindir`, for
+    /// Windows DLLs), preferring the static library if `rb-sys` links it
+    /// statically. Returns `None` if no library was found (i.e. for
+    /// TruffleRuby).
+    pub fn libruby_path(&self) -> Option<PathBuf> {
+        let mut dirs = Vec::new();
+        dirs.extend(self.vars.get("LIBDIR").map(PathBuf::from));
+        dirs.extend(self.get_rbconfig_dir("libdir"));
+        dirs.extend(self.get_rbconfig_dir("bindir"));
+
+        let mut keys = vec!["LIBRUBY_SO", "LIBRUBY"];
+        if self.is_ruby_static() {
+            keys.insert(0, "LIBRUBY_A");
+        }
+
+        let mut names: Vec<String> = keys
+            .iter()
+            .filter_map(|key| self.get_rbconfig_value(key))
+            .filter(|name| !name.is_empty())
+            .map(String::from)
+            .collect();
+
+        if let Some(lib) = self.vars.get("LIB") {
+            for ext in ["a", "so", "dylib", "dll"] {
+                names.push(format!("lib{}.{}", lib, ext));
+            }
+        }
+
+        symbols::find_library(&dirs, &names)
+    }
+
+    /// Checks which of `symbols` are defined by libruby, using `nm` (or the
+    /// `NM` environment variable). Returns `None` if libruby could not be
+    /// found or inspected.
+    pub fn libruby_symbols(&self, symbols: &[&str]) -> Option<HashSet<String>> {
+        let defined = symbols::defined_symbols(&self.libruby_path()?)?;
+        let found = symbols.iter().filter(|sym| defined.contains(**sym));
+
+        Some(found.map(|sym| sym.to_string()).collect())
+    }
+
+    /// Prints a `ruby_have_symbol_<name>` cfg for each of `symbols` which is
+    /// defined by libruby (i.e. `#[cfg(ruby_have_symbol_rb_gc_compact)]`), so
+    /// functions which only exist in some builds of Ruby can be used without
+    /// failing to link.
+    ///
+    /// If libruby could not be inspected, none of the cfgs are set.
+    ///
+    /// ```no_run
+    /// // In your crate's build.rs
+    /// pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let rb_env = rb_sys_env::activate()?;
+    ///
+    ///     rb_env.print_libruby_symbol_cfgs(&["rb_gc_compact", "rb_yjit_enabled_p"]);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn print_libruby_symbol_cfgs(&self, symbols: &[&str]) {
+        let found = self.libruby_symbols(symbols).unwrap_or_default();
+
+        for sym in symbols {
+            rustc_cfg!(found.contains(*sym), "ruby_have_symbol_{}", sym);
+        }
+
+        println!("cargo:rerun-if-env-changed=NM");
+    }
+
     /// Prints args for rustc (i.e. `cargo:rustc-cfg=...`).
     pub fn print_cargo_rustc_cfg(&self) {
         self.defines.print_cargo_rustc_cfg();
@@ -108,3 +309,145 @@ impl Default for RbEnv {
         Self { defines, vars }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rb_env(vars: &[(&str, &str)]) -> RbEnv {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let vars = Rc::new(vars);
+        let defines = Defines::from_raw_environment(vars.clone());
+
+        RbEnv { defines, vars }
+    }
+
+    #[test]
+    fn test_abi_version_from_rbconfig() {
+        let env = rb_env(&[
+            ("MAJOR", "3"),
+            ("MINOR", "4"),
+            ("TEENY", "1"),
+            ("RBCONFIG_RUBY_API_VERSION", "3.3"),
+        ]);
+
+        assert_eq!(env.abi_version(), (3, 3));
+    }
+
+    #[test]
+    fn test_abi_version_falls_back_to_ruby_version() {
+        let env = rb_env(&[("MAJOR", "3"), ("MINOR", "2"), ("TEENY", "5")]);
+
+        assert_eq!(env.abi_version(), (3, 2));
+    }
+
+    #[test]
+    fn test_install_dirs() {
+        let env = rb_env(&[
+            (
+                "RBCONFIG_sitearchdir",
+                "/opt/ruby/lib/ruby/site_ruby/3.3.0/x86_64-linux",
+            ),
+            ("RBCONFIG_vendorarchdir", ""),
+        ]);
+
+        assert_eq!(
+            env.sitearch_dir(),
+            Some(PathBuf::from(
+                "/opt/ruby/lib/ruby/site_ruby/3.3.0/x86_64-linux"
+            ))
+        );
+        assert_eq!(env.vendorarch_dir(), None);
+        assert_eq!(rb_env(&[]).sitearch_dir(), None);
+    }
+
+    #[test]
+    fn test_build_info_source() {
+        let env = rb_env(&[
+            ("MAJOR", "3"),
+            ("MINOR", "3"),
+            ("TEENY", "6"),
+            ("RUBY_STATIC", "true"),
+            ("RBCONFIG_RUBY_API_VERSION", "3.3"),
+            ("RBCONFIG_platform", "arm64-darwin23"),
+            ("RBCONFIG_ruby_install_name", "truffleruby"),
+        ]);
+        let source = env.build_info_source();
+
+        assert!(source.contains("pub const RUBY_VERSION: &str = \"3.3.6\";\n"));
+        assert!(source.contains("pub const RUBY_API_VERSION: &str = \"3.3\";\n"));
+        assert!(source.contains("pub const PLATFORM: &str = \"arm64-darwin23\";\n"));
+        assert!(source.contains("pub const ENGINE: &str = \"truffleruby\";\n"));
+        assert!(source.contains("pub const RUBY_STATIC: bool = true;\n"));
+    }
+
+    #[test]
+    fn test_build_info_source_engine() {
+        let env = rb_env(&[
+            ("MAJOR", "3"),
+            ("MINOR", "3"),
+            ("TEENY", "6"),
+            ("RBCONFIG_ruby_install_name", "ruby3.3"),
+        ]);
+        assert!(env
+            .build_info_source()
+            .contains("pub const ENGINE: &str = \"ruby\";\n"));
+
+        let env = rb_env(&[
+            ("MAJOR", "3"),
+            ("MINOR", "3"),
+            ("TEENY", "6"),
+            ("ENGINE", "mri"),
+            ("RBCONFIG_ruby_install_name", "truffleruby"),
+        ]);
+        assert!(env
+            .build_info_source()
+            .contains("pub const ENGINE: &str = \"ruby\";\n"));
+    }
+
+    #[test]
+    fn test_build_info_source_defaults() {
+        let env = rb_env(&[("MAJOR", "3"), ("MINOR", "2"), ("TEENY", "0")]);
+        let source = env.build_info_source();
+
+        assert!(source.contains("pub const PLATFORM: &str = \"unknown\";\n"));
+        assert!(source.contains("pub const ENGINE: &str = \"ruby\";\n"));
+        assert!(source.contains("pub const RUBY_STATIC: bool = false;\n"));
+    }
+
+    #[test]
+    fn test_libruby_path() {
+        let dir = std::env::temp_dir().join(format!(
+            "rb-sys-env-test-libruby-path-{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("libruby.so.3.3.0"), "").unwrap();
+        std::fs::write(dir.join("libruby-static.a"), "").unwrap();
+
+        let libdir = dir.to_str().unwrap();
+        let env = rb_env(&[
+            ("LIBDIR", libdir),
+            ("LIB", "ruby"),
+            ("RBCONFIG_LIBRUBY_SO", "libruby.so.3.3.0"),
+            ("RBCONFIG_LIBRUBY_A", "libruby-static.a"),
+        ]);
+        assert_eq!(env.libruby_path(), Some(dir.join("libruby.so.3.3.0")));
+
+        let env = rb_env(&[
+            ("LIBDIR", libdir),
+            ("RUBY_STATIC", "true"),
+            ("RBCONFIG_LIBRUBY_SO", "libruby.so.3.3.0"),
+            ("RBCONFIG_LIBRUBY_A", "libruby-static.a"),
+        ]);
+        assert_eq!(env.libruby_path(), Some(dir.join("libruby-static.a")));
+
+        let env = rb_env(&[("LIBDIR", libdir), ("LIB", "truffleruby")]);
+        assert_eq!(env.libruby_path(), None);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,124 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// Lists the global symbols defined by the library at `path`, using `nm` (or
+/// the `NM` environment variable, for cross-compiling). Returns `None` if `nm`
+/// could not be run.
+pub(crate) fn defined_symbols(path: &Path) -> Option<HashSet<String>> {
+    let nm = std::env::var("NM").unwrap_or_else(|_| "nm".to_string());
+    let mut cmd = Command::new(nm);
+
+    // Shared libraries are usually stripped, leaving only the dynamic symbol
+    // table.
+    if is_shared_library(path) && !is_macos() {
+        cmd.arg("-D");
+    } else {
+        cmd.arg("-g");
+    }
+
+    let output = cmd.arg(path).output().ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    Some(parse_nm_output(&stdout, is_macos()))
+}
+
+/// Parses the output of `nm`, keeping only defined symbols. Mach-O symbols
+/// have a leading underscore, which is removed if `strip_underscore` is set.
+pub(crate) fn parse_nm_output(output: &str, strip_underscore: bool) -> HashSet<String> {
+    let mut symbols = HashSet::new();
+
+    for line in output.lines() {
+        let mut fields = line.split_whitespace();
+
+        // Undefined symbols have no address, so defined ones have 3 fields.
+        let (Some(_addr), Some(kind), Some(name), None) =
+            (fields.next(), fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+
+        if matches!(kind, "U" | "w" | "v") {
+            continue;
+        }
+
+        // GNU `nm -D` appends the symbol version (i.e. `rb_foo@@RUBY_3.3`).
+        let name = name.split('@').next().unwrap_or(name);
+        let name = if strip_underscore {
+            name.strip_prefix('_').unwrap_or(name)
+        } else {
+            name
+        };
+
+        symbols.insert(name.to_string());
+    }
+
+    symbols
+}
+
+/// Finds the first of the candidate file names which exists in one of the
+/// directories.
+pub(crate) fn find_library(dirs: &[PathBuf], names: &[String]) -> Option<PathBuf> {
+    names
+        .iter()
+        .flat_map(|name| dirs.iter().map(move |dir| dir.join(name)))
+        .find(|path| path.is_file())
+}
+
+fn is_shared_library(path: &Path) -> bool {
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+
+    name.ends_with(".so") || name.contains(".so.")
+}
+
+fn is_macos() -> bool {
+    matches!(
+        std::env::var("CARGO_CFG_TARGET_OS").as_deref(),
+        Ok("macos") | Ok("ios")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_nm_output() {
+        let output = "\
+0000000000123450 T rb_gc_compact
+0000000000123460 T rb_gc_start@@RUBY_3.3
+0000000000200000 D rb_cObject
+                 U malloc
+                 w __gmon_start__
+0000000000123470 W rb_weak_thing
+";
+        let symbols = parse_nm_output(output, false);
+
+        assert!(symbols.contains("rb_gc_compact"));
+        assert!(symbols.contains("rb_gc_start"));
+        assert!(symbols.contains("rb_cObject"));
+        assert!(symbols.contains("rb_weak_thing"));
+        assert!(!symbols.contains("malloc"));
+        assert!(!symbols.contains("__gmon_start__"));
+    }
+
+    #[test]
+    fn test_parse_nm_output_strips_mach_o_underscore() {
+        let output = "\
+00000000000a1b20 T _rb_gc_compact
+                 U _malloc
+";
+        let symbols = parse_nm_output(output, true);
+
+        assert!(symbols.contains("rb_gc_compact"));
+        assert!(!symbols.contains("_rb_gc_compact"));
+        assert!(!symbols.contains("malloc"));
+    }
+}
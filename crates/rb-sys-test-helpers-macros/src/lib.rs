@@ -29,6 +29,25 @@ use syn::{spanned::Spanned, ItemFn};
 /// fn test_with_stress() {
 ///    unsafe { rb_sys::rb_eval_string("puts 'GC is stressing me out.'\0".as_ptr() as _) };
 /// }
+///
+/// #[ruby_test(gc_compact)]
+/// fn test_with_compaction() {
+///    unsafe { rb_sys::rb_eval_string("puts 'Objects are moving around.'\0".as_ptr() as _) };
+/// }
+///
+/// // Runs the test under the default GC, `gc_stress` and `gc_compact`,
+/// // reporting which one failed.
+/// #[ruby_test(gc_matrix)]
+/// fn test_with_every_gc_mode() {
+///    unsafe { rb_sys::rb_eval_string("puts 'Which GC am I running under?'\0".as_ptr() as _) };
+/// }
+///
+/// // Evaluates Ruby string literals as frozen, as with
+/// // `--enable-frozen-string-literal`.
+/// #[ruby_test(frozen_strings)]
+/// fn test_with_frozen_strings() {
+///    unsafe { rb_sys::rb_eval_string("'literal'.frozen? or raise\0".as_ptr() as _) };
+/// }
 /// ```
 #[proc_macro_attribute]
 pub fn ruby_test(args: TokenStream, input: TokenStream) -> TokenStream {
@@ -37,18 +56,32 @@ pub fn ruby_test(args: TokenStream, input: TokenStream) -> TokenStream {
         Err(err) => return err.to_compile_error().into(),
     };
 
-    let mut gc_stress = false;
+    let mut gc_mode = None;
+    let mut frozen_strings = false;
 
     for arg in args {
         match arg {
             TokenTree::Ident(ident) => match ident.to_string().as_str() {
-                "gc_stress" => gc_stress = true,
+                mode @ ("gc_stress" | "gc_compact" | "gc_matrix") => {
+                    if gc_mode.is_some() {
+                        return syn::Error::new(
+                            ident.span().into(),
+                            "only one of gc_stress, gc_compact and gc_matrix can be used",
+                        )
+                        .to_compile_error()
+                        .into();
+                    }
+
+                    gc_mode = Some(mode.to_owned());
+                }
+                "frozen_strings" => frozen_strings = true,
                 kw => {
                     return syn::Error::new(kw.span(), format!("unknown argument: {}", kw))
                         .to_compile_error()
                         .into();
                 }
             },
+            TokenTree::Punct(punct) if punct.as_char() == ',' => {}
             _ => {
                 return syn::Error::new(arg.span().into(), format!("expected identifier: {}", arg))
                     .to_compile_error()
@@ -62,9 +95,9 @@ pub fn ruby_test(args: TokenStream, input: TokenStream) -> TokenStream {
     let vis = input.vis;
     let sig = &input.sig;
 
-    let block = if gc_stress {
+    let block = if frozen_strings {
         quote! {
-            rb_sys_test_helpers::with_gc_stress(|| {
+            rb_sys_test_helpers::with_frozen_string_literals(|| {
                 #block
             })
         }
@@ -72,6 +105,25 @@ pub fn ruby_test(args: TokenStream, input: TokenStream) -> TokenStream {
         quote! { #block }
     };
 
+    let block = match gc_mode.as_deref() {
+        Some("gc_stress") => quote! {
+            rb_sys_test_helpers::with_gc_stress(|| {
+                #block
+            })
+        },
+        Some("gc_compact") => quote! {
+            rb_sys_test_helpers::with_gc_compact(|| {
+                #block
+            })
+        },
+        Some("gc_matrix") => quote! {
+            rb_sys_test_helpers::with_gc_matrix(|| {
+                #block
+            })
+        },
+        _ => quote! { #block },
+    };
+
     let block = quote! {
         let ret = {
             #block
@@ -0,0 +1,89 @@
+use crate::setup_ruby_unguarded;
+use criterion::{measurement::WallTime, BenchmarkGroup, BenchmarkId, Criterion};
+use rb_sys::{rb_gc_register_mark_object, VALUE};
+use std::{
+    ops::{Deref, DerefMut},
+    sync::Once,
+};
+
+/// Creates a criterion benchmark group which runs against a live Ruby VM.
+///
+/// The VM is set up on the current thread the first time this is called, and
+/// is never torn down. Since Ruby can only be initialized once per process,
+/// callers should not set it up themselves.
+///
+/// ### Example
+///
+/// ```no_run
+/// use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+/// use rb_sys_test_helpers::{rstring, ruby_bench_group};
+///
+/// fn bench_strlen(c: &mut Criterion) {
+///     let mut group = ruby_bench_group(c, "rb_str_strlen");
+///
+///     for len in [8, 1024] {
+///         let string = "a".repeat(len);
+///         let string = rstring!(string);
+///
+///         group.bench_value(BenchmarkId::from_parameter(len), string, |s| unsafe {
+///             rb_sys::rb_str_strlen(s)
+///         });
+///     }
+///
+///     group.finish();
+/// }
+///
+/// criterion_group!(benches, bench_strlen);
+/// criterion_main!(benches);
+/// ```
+pub fn ruby_bench_group<'a>(c: &'a mut Criterion, name: &str) -> RubyBenchGroup<'a> {
+    static INIT: Once = Once::new();
+
+    INIT.call_once(|| unsafe { setup_ruby_unguarded() });
+
+    RubyBenchGroup {
+        group: c.benchmark_group(name),
+    }
+}
+
+/// A criterion [`BenchmarkGroup`] whose inputs are Ruby objects. Derefs to
+/// the underlying group, so it can be configured as usual.
+pub struct RubyBenchGroup<'a> {
+    group: BenchmarkGroup<'a, WallTime>,
+}
+
+impl<'a> RubyBenchGroup<'a> {
+    /// Benchmarks `f` with the given Ruby object as input. The object is
+    /// registered with `rb_gc_register_mark_object`, so it will never be
+    /// garbage collected while the benchmark runs (or afterwards).
+    pub fn bench_value<O, F>(&mut self, id: BenchmarkId, value: VALUE, mut f: F) -> &mut Self
+    where
+        F: FnMut(VALUE) -> O,
+    {
+        unsafe { rb_gc_register_mark_object(value) };
+
+        self.group
+            .bench_with_input(id, &value, |b, &value| b.iter(|| f(value)));
+
+        self
+    }
+
+    /// Consumes the group and generates the summary reports.
+    pub fn finish(self) {
+        self.group.finish();
+    }
+}
+
+impl<'a> Deref for RubyBenchGroup<'a> {
+    type Target = BenchmarkGroup<'a, WallTime>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.group
+    }
+}
+
+impl<'a> DerefMut for RubyBenchGroup<'a> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.group
+    }
+}
@@ -0,0 +1,58 @@
+use crate::gc::propagate;
+use rb_sys::exception::protect;
+use rb_sys::{rb_eval_string, rb_funcall, rb_hash_aset, rb_hash_new, rb_id2sym, rb_intern, VALUE};
+use std::panic::{self, AssertUnwindSafe};
+
+/// Runs `f` with frozen string literals enabled, as if every Ruby file and
+/// `eval`ed string had the `# frozen_string_literal: true` magic comment (or
+/// Ruby was started with `--enable-frozen-string-literal`). The previous
+/// setting is restored afterwards, even if `f` raised or panicked.
+///
+/// This only applies to Ruby code compiled while `f` runs, which helps catch
+/// extensions that mutate strings which came from a literal.
+///
+/// ### Example
+///
+/// ```
+/// use rb_sys_test_helpers::{eval, with_frozen_string_literals, with_ruby_vm};
+///
+/// with_ruby_vm(|| {
+///     let frozen = with_frozen_string_literals(|| eval!("'hello'.frozen?"));
+///
+///     assert_eq!(frozen, rb_sys::Qtrue as rb_sys::VALUE);
+/// })
+/// .unwrap();
+/// ```
+pub fn with_frozen_string_literals<R, F>(f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    unsafe {
+        let previous = set_frozen_string_literal(rb_sys::Qtrue as VALUE);
+        let result = panic::catch_unwind(AssertUnwindSafe(|| protect(f)));
+        set_frozen_string_literal(previous);
+
+        propagate(result)
+    }
+}
+
+/// Sets the `frozen_string_literal` compile option, returning the previous
+/// value.
+unsafe fn set_frozen_string_literal(value: VALUE) -> VALUE {
+    let previous = rb_eval_string(
+        "RubyVM::InstructionSequence.compile_option[:frozen_string_literal]\0".as_ptr() as _,
+    );
+    let iseq = rb_eval_string("RubyVM::InstructionSequence\0".as_ptr() as _);
+    let options = rb_hash_new();
+    let key = rb_id2sym(rb_intern("frozen_string_literal\0".as_ptr() as _));
+
+    rb_hash_aset(options, key, value);
+    rb_funcall(
+        iseq,
+        rb_intern("compile_option=\0".as_ptr() as _),
+        1,
+        options,
+    );
+
+    previous
+}
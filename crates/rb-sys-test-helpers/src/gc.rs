@@ -0,0 +1,262 @@
+use rb_sys::exception::{protect, RubyException};
+use rb_sys::{
+    rb_cObject, rb_const_get, rb_funcall, rb_gc_stat, rb_id2sym, rb_intern, rb_respond_to, Qnil,
+    Qtrue, VALUE,
+};
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+
+/// A GC configuration to run a test under.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GcMode {
+    /// The GC as it is normally configured.
+    Default,
+    /// `GC.stress = true`, which runs the GC on every allocation.
+    Stress,
+    /// Compacts the heap before and after, with `GC.auto_compact = true` in
+    /// between, so objects move while the test runs. Platforms which do not
+    /// support compaction run the test as in `Default`.
+    Compact,
+}
+
+impl GcMode {
+    /// All of the modes, in the order `with_gc_matrix` runs them.
+    pub const ALL: [GcMode; 3] = [GcMode::Default, GcMode::Stress, GcMode::Compact];
+
+    /// Configures the GC, returning the previous setting to restore.
+    unsafe fn enter(self) -> VALUE {
+        let gc = gc_module();
+
+        match self {
+            GcMode::Default => Qnil as VALUE,
+            GcMode::Stress => {
+                let previous = rb_funcall(gc, intern("stress\0"), 0);
+                rb_funcall(gc, intern("stress=\0"), 1, Qtrue as VALUE);
+                previous
+            }
+            GcMode::Compact => {
+                try_compact(gc);
+
+                if rb_respond_to(gc, intern("auto_compact=\0")) == 0 {
+                    return Qnil as VALUE;
+                }
+
+                let previous = rb_funcall(gc, intern("auto_compact\0"), 0);
+                let _ = protect(|| rb_funcall(gc, intern("auto_compact=\0"), 1, Qtrue as VALUE));
+                previous
+            }
+        }
+    }
+
+    /// Restores the setting returned by `enter`.
+    unsafe fn exit(self, previous: VALUE) {
+        let gc = gc_module();
+
+        match self {
+            GcMode::Default => {}
+            GcMode::Stress => {
+                rb_funcall(gc, intern("stress=\0"), 1, previous);
+            }
+            GcMode::Compact => {
+                if rb_respond_to(gc, intern("auto_compact=\0")) != 0 {
+                    let _ = protect(|| rb_funcall(gc, intern("auto_compact=\0"), 1, previous));
+                }
+
+                try_compact(gc);
+            }
+        }
+    }
+
+    /// Runs `f` in this mode, restoring the GC configuration afterwards even
+    /// if it raised or panicked.
+    fn run<R, F: FnOnce() -> R>(self, f: F) -> std::thread::Result<Result<R, RubyException>> {
+        unsafe {
+            let previous = self.enter();
+            let result = panic::catch_unwind(AssertUnwindSafe(|| protect(f)));
+            self.exit(previous);
+
+            result
+        }
+    }
+}
+
+impl fmt::Display for GcMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GcMode::Default => f.write_str("default"),
+            GcMode::Stress => f.write_str("stress"),
+            GcMode::Compact => f.write_str("compact"),
+        }
+    }
+}
+
+/// Runs a test with the heap compacted before and after, and auto-compaction
+/// enabled while it runs, to help find references which are not updated when
+/// objects move.
+///
+/// ### Example
+///
+/// ```
+/// use rb_sys_test_helpers::{with_gc_compact, with_ruby_vm};
+///
+/// with_ruby_vm(|| {
+///     let len = with_gc_compact(|| unsafe {
+///         let string = rb_sys::rb_utf8_str_new_cstr("hello\0".as_ptr() as _);
+///         rb_sys::rb_str_strlen(string)
+///     });
+///
+///     assert_eq!(len, 5);
+/// });
+/// ```
+pub fn with_gc_compact<R, F>(f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    propagate(GcMode::Compact.run(f))
+}
+
+/// Runs a test once under each [`GcMode`]. If it fails, the mode it failed
+/// under is printed before the failure is propagated.
+///
+/// ### Example
+///
+/// ```
+/// use rb_sys_test_helpers::{with_gc_matrix, with_ruby_vm};
+///
+/// with_ruby_vm(|| {
+///     with_gc_matrix(|| unsafe {
+///         let string = rb_sys::rb_utf8_str_new_cstr("hello\0".as_ptr() as _);
+///         assert_eq!(rb_sys::rb_str_strlen(string), 5);
+///     });
+/// });
+/// ```
+pub fn with_gc_matrix<R, F>(f: F) -> R
+where
+    F: Fn() -> R,
+{
+    let mut ret = None;
+
+    for mode in GcMode::ALL {
+        let result = mode.run(&f);
+
+        if !matches!(result, Ok(Ok(_))) {
+            eprintln!("test failed under the `{}` GC mode", mode);
+        }
+
+        ret = Some(propagate(result));
+    }
+
+    ret.expect("no GC modes")
+}
+
+/// The change in some `GC.stat` counters while a closure ran, as returned by
+/// [`gc_stat_delta`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GcStatDelta {
+    /// Objects allocated (`GC.stat(:total_allocated_objects)`).
+    pub allocated_objects: i64,
+    /// Objects freed (`GC.stat(:total_freed_objects)`).
+    pub freed_objects: i64,
+    /// Major GCs run (`GC.stat(:major_gc_count)`).
+    pub major_gc_count: i64,
+    /// Minor GCs run (`GC.stat(:minor_gc_count)`).
+    pub minor_gc_count: i64,
+    /// Heap pages allocated, or released when negative
+    /// (`GC.stat(:heap_allocated_pages)`).
+    pub heap_pages: i64,
+}
+
+impl GcStatDelta {
+    /// The number of GCs run, major or minor.
+    pub fn gc_count(&self) -> i64 {
+        self.major_gc_count + self.minor_gc_count
+    }
+}
+
+/// Runs `f` and returns how much some `GC.stat` counters changed while it
+/// ran, i.e. to check that an operation allocates fewer than N objects or
+/// triggers no major GC.
+///
+/// The counters are global, so anything else allocating at the same time
+/// (i.e. another Ruby thread) is counted too.
+///
+/// ### Example
+///
+/// ```
+/// use rb_sys_test_helpers::{eval, gc_stat_delta, with_ruby_vm};
+///
+/// with_ruby_vm(|| {
+///     let delta = gc_stat_delta(|| {
+///         eval!("1 + 1");
+///     });
+///
+///     assert_eq!(delta.major_gc_count, 0);
+/// });
+/// ```
+pub fn gc_stat_delta<F: FnOnce()>(f: F) -> GcStatDelta {
+    unsafe {
+        let before = gc_stat_snapshot();
+        f();
+        let after = gc_stat_snapshot();
+
+        GcStatDelta {
+            allocated_objects: after.allocated_objects - before.allocated_objects,
+            freed_objects: after.freed_objects - before.freed_objects,
+            major_gc_count: after.major_gc_count - before.major_gc_count,
+            minor_gc_count: after.minor_gc_count - before.minor_gc_count,
+            heap_pages: after.heap_pages - before.heap_pages,
+        }
+    }
+}
+
+/// The current values of the counters in `GcStatDelta`. The symbols are
+/// created before any counter is read, so they are not counted.
+unsafe fn gc_stat_snapshot() -> GcStatDelta {
+    let keys = [
+        gc_stat_key("total_allocated_objects\0"),
+        gc_stat_key("total_freed_objects\0"),
+        gc_stat_key("major_gc_count\0"),
+        gc_stat_key("minor_gc_count\0"),
+        gc_stat_key("heap_allocated_pages\0"),
+    ];
+    let [allocated_objects, freed_objects, major_gc_count, minor_gc_count, heap_pages] =
+        keys.map(|key| rb_gc_stat(key) as i64);
+
+    GcStatDelta {
+        allocated_objects,
+        freed_objects,
+        major_gc_count,
+        minor_gc_count,
+        heap_pages,
+    }
+}
+
+unsafe fn gc_stat_key(name: &str) -> VALUE {
+    rb_id2sym(intern(name))
+}
+
+/// Turns a caught result (i.e. from `GcMode::run`) back into a panic or Ruby
+/// exception.
+pub(crate) fn propagate<R>(result: std::thread::Result<Result<R, RubyException>>) -> R {
+    match result {
+        Ok(Ok(value)) => value,
+        Ok(Err(exception)) => unsafe { exception.raise() },
+        Err(payload) => panic::resume_unwind(payload),
+    }
+}
+
+/// Compacts the heap, if the platform supports it.
+unsafe fn try_compact(gc: VALUE) {
+    if rb_respond_to(gc, intern("compact\0")) != 0 {
+        // Raises `NotImplementedError` on platforms without compaction.
+        let _ = protect(|| rb_funcall(gc, intern("compact\0"), 0));
+    }
+}
+
+unsafe fn gc_module() -> VALUE {
+    rb_const_get(rb_cObject, intern("GC\0"))
+}
+
+unsafe fn intern(name: &str) -> rb_sys::ID {
+    rb_intern(name.as_ptr() as _)
+}
@@ -0,0 +1,76 @@
+use crate::gc::propagate;
+use rb_sys::exception::protect;
+use rb_sys::{rb_ary_entry, rb_eval_string, rb_funcall, rb_intern, rb_num2long, VALUE};
+use std::panic::{self, AssertUnwindSafe};
+
+/// Asserts that `f` releases the GVL (i.e. with `rb_thread_call_without_gvl`)
+/// by running a Ruby thread which counts up in the background, and checking
+/// that it made progress while `f` ran. Returns the result of `f`.
+///
+/// A Ruby thread can only run while the GVL is released, so `f` must block
+/// for long enough to let it be scheduled (a few milliseconds is plenty).
+/// The thread is stopped afterwards, even if `f` raised or panicked.
+///
+/// ### Example
+///
+/// ```
+/// use rb_sys_test_helpers::{assert_gvl_released, with_ruby_vm};
+/// use std::os::raw::c_void;
+///
+/// unsafe extern "C" fn sleep(_: *mut c_void) -> *mut c_void {
+///     std::thread::sleep(std::time::Duration::from_millis(50));
+///     std::ptr::null_mut()
+/// }
+///
+/// with_ruby_vm(|| {
+///     assert_gvl_released(|| unsafe {
+///         let null = std::ptr::null_mut();
+///         rb_sys::rb_thread_call_without_gvl(Some(sleep), null, None, null);
+///     });
+/// })
+/// .unwrap();
+/// ```
+#[track_caller]
+pub fn assert_gvl_released<R, F>(f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    unsafe {
+        let (thread, counter) = spawn_counter();
+        let before = read_counter(counter);
+        let result = panic::catch_unwind(AssertUnwindSafe(|| protect(f)));
+        let after = read_counter(counter);
+
+        rb_funcall(thread, rb_intern("kill\0".as_ptr() as _), 0);
+        rb_funcall(thread, rb_intern("join\0".as_ptr() as _), 0);
+
+        let result = propagate(result);
+
+        assert!(
+            after > before,
+            "expected the GVL to be released, but no other Ruby thread ran"
+        );
+
+        result
+    }
+}
+
+/// Starts a Ruby thread which increments the first element of an array in a
+/// loop, and waits for it to start. Returns the thread and the array.
+unsafe fn spawn_counter() -> (VALUE, VALUE) {
+    let pair = rb_eval_string(
+        "counter = [0]; [Thread.new { loop { counter[0] += 1 } }, counter]\0".as_ptr() as _,
+    );
+    let thread = rb_ary_entry(pair, 0);
+    let counter = rb_ary_entry(pair, 1);
+
+    while read_counter(counter) == 0 {
+        rb_sys::rb_thread_schedule();
+    }
+
+    (thread, counter)
+}
+
+unsafe fn read_counter(counter: VALUE) -> i64 {
+    rb_num2long(rb_ary_entry(counter, 0)) as i64
+}
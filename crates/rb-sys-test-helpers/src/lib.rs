@@ -1,17 +1,31 @@
 #![allow(rustdoc::bare_urls)]
 #![doc = include_str!("../readme.md")]
+#[cfg(feature = "criterion")]
+mod bench;
+mod frozen_strings;
+mod gc;
+mod gvl;
+mod load_path;
 mod once_cell;
 mod ruby_exception;
 mod ruby_test_executor;
+pub mod samples;
 mod utils;
 
 use rb_sys::{rb_errinfo, rb_intern, rb_set_errinfo, Qnil, VALUE};
 use ruby_test_executor::global_executor;
 use std::{error::Error, mem::MaybeUninit, panic::UnwindSafe};
 
+#[cfg(feature = "criterion")]
+pub use bench::{ruby_bench_group, RubyBenchGroup};
+pub use frozen_strings::with_frozen_string_literals;
+pub use gc::{gc_stat_delta, with_gc_compact, with_gc_matrix, GcMode, GcStatDelta};
+pub use gvl::assert_gvl_released;
+pub use load_path::with_temp_load_path;
 pub use rb_sys_test_helpers_macros::*;
 pub use ruby_exception::RubyException;
 pub use ruby_test_executor::{cleanup_ruby, setup_ruby, setup_ruby_unguarded};
+pub use utils::{assert_method_visibility, method_visibility, ruby_class_name};
 
 /// Run a given function with inside of a Ruby VM.
 ///
@@ -163,4 +177,275 @@ mod tests {
         })
         .unwrap();
     }
+
+    #[test]
+    fn test_with_gc_matrix_runs_each_mode() {
+        let stress = with_ruby_vm(|| {
+            let runs = std::cell::RefCell::new(vec![]);
+
+            with_gc_matrix(|| {
+                runs.borrow_mut()
+                    .push(eval!("GC.stress") == rb_sys::Qtrue as VALUE);
+            });
+
+            runs.into_inner()
+        })
+        .unwrap();
+
+        assert_eq!(stress, [false, true, false]);
+        assert_eq!(
+            with_ruby_vm(|| eval!("GC.stress")).unwrap(),
+            rb_sys::Qfalse as VALUE
+        );
+    }
+
+    #[test]
+    fn test_with_gc_matrix_restores_stress_after_exception() {
+        let result = with_ruby_vm(|| {
+            protect(|| {
+                with_gc_matrix(|| {
+                    if eval!("GC.stress") == rb_sys::Qtrue as VALUE {
+                        eval!("raise 'only fails under stress'");
+                    }
+                })
+            })
+            .is_err()
+        });
+
+        assert!(result.unwrap());
+        assert_eq!(
+            with_ruby_vm(|| eval!("GC.stress")).unwrap(),
+            rb_sys::Qfalse as VALUE
+        );
+    }
+
+    #[test]
+    fn test_with_gc_compact() {
+        let ret = with_ruby_vm(|| with_gc_compact(|| eval!("1 + 1"))).unwrap();
+
+        assert_eq!(ret, unsafe { rb_sys::rb_int2inum(2) });
+    }
+
+    #[test]
+    fn test_gc_stat_delta() {
+        let (allocating, quiet, collecting) = with_ruby_vm(|| {
+            let allocating = gc_stat_delta(|| {
+                eval!("100.times.map { Object.new }");
+            });
+            let quiet = gc_stat_delta(|| {});
+            let collecting = gc_stat_delta(|| {
+                eval!("GC.start(full_mark: true)");
+            });
+
+            (allocating, quiet, collecting)
+        })
+        .unwrap();
+
+        assert!(allocating.allocated_objects >= 100);
+        assert_eq!(quiet, GcStatDelta::default());
+        assert_eq!(collecting.major_gc_count, 1);
+        assert!(collecting.gc_count() >= 1);
+    }
+
+    unsafe extern "C" fn sleep_briefly(_: *mut std::os::raw::c_void) -> *mut std::os::raw::c_void {
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        std::ptr::null_mut()
+    }
+
+    #[test]
+    fn test_assert_gvl_released() {
+        let ret = with_ruby_vm(|| {
+            assert_gvl_released(|| unsafe {
+                rb_sys::rb_thread_call_without_gvl(
+                    Some(sleep_briefly),
+                    std::ptr::null_mut(),
+                    None,
+                    std::ptr::null_mut(),
+                );
+
+                42
+            })
+        })
+        .unwrap();
+
+        assert_eq!(ret, 42);
+    }
+
+    #[test]
+    fn test_assert_gvl_released_when_gvl_is_held() {
+        let result = with_ruby_vm(|| {
+            std::panic::catch_unwind(|| {
+                assert_gvl_released(|| {
+                    std::thread::sleep(std::time::Duration::from_millis(50));
+                })
+            })
+        })
+        .unwrap();
+
+        let message = result.unwrap_err();
+        let message = message.downcast_ref::<String>().unwrap();
+
+        assert!(message.contains("expected the GVL to be released"));
+    }
+
+    #[test]
+    fn test_with_frozen_string_literals() {
+        let (frozen, mutated, after) = with_ruby_vm(|| {
+            let (frozen, mutated) = with_frozen_string_literals(|| {
+                (
+                    eval!("'hello'.frozen?"),
+                    protect(|| eval!("'hello' << ' world'")).is_ok(),
+                )
+            });
+
+            (frozen, mutated, eval!("'hello'.frozen?"))
+        })
+        .unwrap();
+
+        assert_eq!(frozen, rb_sys::Qtrue as VALUE);
+        assert!(!mutated);
+        assert_eq!(after, rb_sys::Qfalse as VALUE);
+    }
+
+    #[test]
+    fn test_with_temp_load_path() {
+        let dir = std::env::temp_dir().join(format!("rb-sys-load-path-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("rb_sys_load_path_fixture.rb"),
+            "RB_SYS_FIXTURE = 1",
+        )
+        .unwrap();
+
+        let (required, len_before, len_after) = with_ruby_vm(move || {
+            let len_before = eval!("$LOAD_PATH.size");
+            let required = with_temp_load_path(&dir, || {
+                eval!("require 'rb_sys_load_path_fixture'; RB_SYS_FIXTURE")
+            });
+            let len_after = eval!("$LOAD_PATH.size");
+
+            (required, len_before, len_after)
+        })
+        .unwrap();
+
+        assert_eq!(required, unsafe { rb_sys::rb_int2inum(1) });
+        assert_eq!(len_before, len_after);
+    }
+
+    #[test]
+    fn test_with_temp_load_path_removes_entry_after_panic() {
+        let dir = std::env::temp_dir().join("rb-sys-load-path-panic");
+
+        let (panicked, included) = with_ruby_vm(move || {
+            let panicked = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                with_temp_load_path(&dir, || panic!("oh no"))
+            }))
+            .is_err();
+            let included = eval!("$LOAD_PATH.any? { |p| p.end_with?('rb-sys-load-path-panic') }");
+
+            (panicked, included)
+        })
+        .unwrap();
+
+        assert!(panicked);
+        assert_eq!(included, rb_sys::Qfalse as VALUE);
+    }
+
+    #[test]
+    fn test_samples() {
+        use samples::*;
+
+        with_ruby_vm(|| {
+            assert_ruby_class!(sample_string(), "String");
+            assert_ruby_class!(sample_long_string(), "String");
+            assert_ruby_class!(sample_symbol(), "Symbol");
+            assert_ruby_class!(sample_bignum(), "Integer");
+            assert_ruby_class!(sample_float(), "Float");
+            assert_ruby_class!(sample_array(), "Array");
+            assert_ruby_class!(sample_hash(), "Hash");
+
+            let bignum = sample_bignum();
+            assert!(!rb_sys::FIXNUM_P(bignum));
+
+            let mut inspected = unsafe { rb_sys::rb_inspect(sample_array()) };
+            assert_eq!(
+                unsafe { rstring_to_string!(inspected) },
+                r#"[1, "two", :sample]"#
+            );
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_assert_ruby_class() {
+        with_ruby_vm(|| {
+            assert_ruby_class!(eval!("[]"), "Array");
+            assert_ruby_class!(eval!("nil"), "NilClass");
+            assert_ruby_class!(eval!("Struct.new(:a)"), "Class");
+            assert_ruby_class!(
+                eval!("module RbSysAssertNs; class Foo; end; end; RbSysAssertNs::Foo.new"),
+                "RbSysAssertNs::Foo"
+            );
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_assert_ruby_class_skips_singleton_class() {
+        with_ruby_vm(|| {
+            assert_ruby_class!(eval!("o = Object.new; def o.hi; end; o"), "Object");
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_ruby_class_name_of_anonymous_class() {
+        let name = with_ruby_vm(|| ruby_class_name(eval!("Class.new.new"))).unwrap();
+
+        assert!(name.starts_with("#<Class:0x"), "got {}", name);
+    }
+
+    #[test]
+    fn test_assert_method_defined() {
+        with_ruby_vm(|| {
+            let klass = eval!(
+                "class RbSysMethodsTest; def pub; end; private def priv; end; protected def prot; end; self; end"
+            );
+
+            assert_method_defined!(klass, "pub");
+            assert_method_defined!(klass, "to_s");
+            assert_private_method_defined!(klass, "priv");
+            assert_private_method_defined!(klass, "initialize");
+            assert_eq!(method_visibility(klass, "prot"), Some("protected"));
+            assert_eq!(method_visibility(klass, "missing"), None);
+        })
+        .unwrap();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "expected `RbSysMethodsPrivate#priv` to be public, but it is private"
+    )]
+    fn test_assert_method_defined_when_private() {
+        with_ruby_vm(|| {
+            let klass = eval!("class RbSysMethodsPrivate; private def priv; end; self; end");
+
+            assert_method_defined!(klass, "priv");
+        })
+        .unwrap();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "expected `String#nope` to be defined as a public method, but it is not defined"
+    )]
+    fn test_assert_method_defined_when_missing() {
+        with_ruby_vm(|| assert_method_defined!(eval!("String"), "nope")).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "expected an instance of `Hash`, but its class is `Array`")]
+    fn test_assert_ruby_class_mismatch() {
+        with_ruby_vm(|| assert_ruby_class!(eval!("[]"), "Hash")).unwrap();
+    }
 }
@@ -0,0 +1,58 @@
+use crate::gc::propagate;
+use rb_sys::exception::protect;
+use rb_sys::{rb_ary_delete_at, rb_ary_entry, rb_ary_unshift, rb_gv_get, rb_str_new, VALUE};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+
+/// Runs `f` with `dir` prepended to `$LOAD_PATH`, so that fixture files in it
+/// can be `require`d. The entry is removed afterwards, even if `f` raised or
+/// panicked.
+///
+/// ### Example
+///
+/// ```
+/// use rb_sys_test_helpers::{eval, with_ruby_vm, with_temp_load_path};
+///
+/// let dir = std::env::temp_dir().join("rb-sys-load-path-doctest");
+/// std::fs::create_dir_all(&dir).unwrap();
+/// std::fs::write(dir.join("doctest_fixture.rb"), "DOCTEST_FIXTURE = 42").unwrap();
+///
+/// with_ruby_vm(move || {
+///     let value = with_temp_load_path(&dir, || {
+///         eval!("require 'doctest_fixture'; DOCTEST_FIXTURE")
+///     });
+///
+///     assert_eq!(value, unsafe { rb_sys::rb_int2inum(42) });
+/// })
+/// .unwrap();
+/// ```
+pub fn with_temp_load_path<P, R, F>(dir: P, f: F) -> R
+where
+    P: AsRef<Path>,
+    F: FnOnce() -> R,
+{
+    unsafe {
+        let load_path = rb_gv_get("$LOAD_PATH\0".as_ptr() as _);
+        let dir = dir.as_ref().to_string_lossy();
+        let entry = rb_str_new(dir.as_ptr() as _, dir.len() as _);
+
+        rb_ary_unshift(load_path, entry);
+        let result = panic::catch_unwind(AssertUnwindSafe(|| protect(f)));
+        remove_entry(load_path, entry);
+
+        propagate(result)
+    }
+}
+
+/// Removes the exact `entry` object from `load_path`, leaving any other equal
+/// entries (i.e. one which was already there) in place.
+unsafe fn remove_entry(load_path: VALUE, entry: VALUE) {
+    let len = rb_sys::RARRAY_LEN(load_path);
+
+    for i in 0..len {
+        if rb_ary_entry(load_path, i) == entry {
+            rb_ary_delete_at(load_path, i);
+            return;
+        }
+    }
+}
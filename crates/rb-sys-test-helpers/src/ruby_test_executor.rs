@@ -1,3 +1,5 @@
+use std::any::Any;
+use std::cell::RefCell;
 use std::error::Error;
 use std::panic;
 use std::ptr::addr_of_mut;
@@ -16,6 +18,10 @@ use rb_sys::{
 
 static mut GLOBAL_EXECUTOR: OnceCell<RubyTestExecutor> = OnceCell::new();
 
+thread_local! {
+    static PANIC_LOCATION: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
 pub struct RubyTestExecutor {
     #[allow(clippy::type_complexity)]
     sender: Option<SyncSender<Box<dyn FnOnce() -> Result<(), Box<dyn Error>> + Send>>>,
@@ -88,8 +94,12 @@ impl RubyTestExecutor {
     {
         let (result_sender, result_receiver) = mpsc::sync_channel(1);
 
+        install_panic_hook();
+
         let closure = Box::new(move || -> Result<(), Box<dyn Error>> {
-            let result = panic::catch_unwind(panic::AssertUnwindSafe(f));
+            let _ = take_panic_location();
+            let result = panic::catch_unwind(panic::AssertUnwindSafe(f))
+                .map_err(|payload| (payload, take_panic_location()));
             result_sender.send(result).map_err(Into::into)
         });
 
@@ -101,7 +111,20 @@ impl RubyTestExecutor {
 
         match result_receiver.recv_timeout(self.timeout) {
             Ok(Ok(result)) => Ok(result),
-            Ok(Err(err)) => std::panic::resume_unwind(err),
+            Ok(Err((payload, location))) => {
+                // The panic happened on the executor thread, and resuming it
+                // here does not run the panic hook, so report where it came
+                // from before propagating it to the test.
+                if let Some(location) = location {
+                    eprintln!(
+                        "panicked on the Ruby test thread at {}:\n{}",
+                        location,
+                        panic_message(&*payload)
+                    );
+                }
+
+                std::panic::resume_unwind(payload)
+            }
             Err(_err) => Err(format!("Ruby test timed out after {:?}", self.timeout).into()),
         }
     }
@@ -127,6 +150,39 @@ pub fn global_executor() -> &'static RubyTestExecutor {
     unsafe { &GLOBAL_EXECUTOR }.get_or_init(RubyTestExecutor::start)
 }
 
+/// Installs a panic hook (once) which records where the current thread last
+/// panicked, before running the previous hook.
+fn install_panic_hook() {
+    static INSTALL: Once = Once::new();
+
+    INSTALL.call_once(|| {
+        let previous_hook = panic::take_hook();
+
+        panic::set_hook(Box::new(move |info| {
+            if let Some(location) = info.location() {
+                let location = location.to_string();
+                PANIC_LOCATION.with(|cell| *cell.borrow_mut() = Some(location));
+            }
+
+            previous_hook(info);
+        }));
+    });
+}
+
+fn take_panic_location() -> Option<String> {
+    PANIC_LOCATION.with(|cell| cell.borrow_mut().take())
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "Box<dyn Any>"
+    }
+}
+
 /// Setup the Ruby VM, without cleaning up afterwards.
 ///
 /// ### Safety
@@ -252,6 +308,18 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_panic_hook_records_location() {
+        install_panic_hook();
+
+        let payload = panic::catch_unwind(|| panic!("oh no")).unwrap_err();
+        let location = take_panic_location().expect("location was not recorded");
+
+        assert!(location.contains("ruby_test_executor.rs"), "{}", location);
+        assert_eq!(panic_message(&*payload), "oh no");
+        assert_eq!(take_panic_location(), None);
+    }
+
     rusty_fork_test! {
         #[test]
         fn test_timeout() {
@@ -0,0 +1,63 @@
+//! Ready-made sample objects for tests.
+//!
+//! The functions here return a new object on every call. Like any other
+//! `VALUE` in a `#[ruby_test]`, the objects are kept alive by the GC as long as
+//! they are held in a local variable, since Ruby scans the stack. Do not store
+//! them on the heap (i.e. in a `Vec` or `Box`) without registering them.
+
+use rb_sys::{
+    rb_ary_new_capa, rb_ary_push, rb_float_new, rb_hash_aset, rb_hash_new, rb_id2sym, rb_int2big,
+    rb_int2inum, rb_intern, rb_utf8_str_new, VALUE,
+};
+
+/// A short, embedded UTF-8 string: `"hello world"`.
+pub fn sample_string() -> VALUE {
+    new_string("hello world")
+}
+
+/// A UTF-8 string which is too long to be embedded in its object: `"a" * 1024`.
+pub fn sample_long_string() -> VALUE {
+    new_string(&"a".repeat(1024))
+}
+
+/// A static symbol: `:sample`.
+pub fn sample_symbol() -> VALUE {
+    unsafe { rb_id2sym(rb_intern("sample\0".as_ptr() as _)) }
+}
+
+/// An integer which does not fit in a Fixnum: `isize::MAX`.
+pub fn sample_bignum() -> VALUE {
+    unsafe { rb_int2big(isize::MAX) }
+}
+
+/// A float: `1.5`.
+pub fn sample_float() -> VALUE {
+    unsafe { rb_float_new(1.5) }
+}
+
+/// An array of mixed objects: `[1, "two", :sample]`.
+pub fn sample_array() -> VALUE {
+    unsafe {
+        let array = rb_ary_new_capa(3);
+        rb_ary_push(array, rb_int2inum(1));
+        rb_ary_push(array, new_string("two"));
+        rb_ary_push(array, sample_symbol());
+
+        array
+    }
+}
+
+/// A hash with string and symbol keys: `{ "one" => 1, sample: "two" }`.
+pub fn sample_hash() -> VALUE {
+    unsafe {
+        let hash = rb_hash_new();
+        rb_hash_aset(hash, new_string("one"), rb_int2inum(1));
+        rb_hash_aset(hash, sample_symbol(), new_string("two"));
+
+        hash
+    }
+}
+
+fn new_string(s: &str) -> VALUE {
+    unsafe { rb_utf8_str_new(s.as_ptr() as _, s.len() as _) }
+}
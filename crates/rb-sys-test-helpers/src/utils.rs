@@ -103,3 +103,141 @@ macro_rules! trigger_full_gc {
         }
     };
 }
+
+/// Returns the name of the class of a Ruby value (i.e. `"Array"`).
+///
+/// Singleton classes are skipped, so an object with singleton methods still
+/// reports its original class. Anonymous classes have no name, so they are
+/// shown like `Class#inspect` would (i.e. `"#<Class:0x000055d5>"`).
+pub fn ruby_class_name(value: rb_sys::VALUE) -> String {
+    unsafe {
+        let klass = rb_sys::rb_obj_class(value);
+        let mut path = rb_sys::rb_class_path(klass);
+
+        rstring_to_string!(path)
+    }
+}
+
+/// Asserts that a Ruby value is an instance of the class with the given name
+/// (not including subclasses). On failure, the actual class is printed.
+///
+/// ### Example
+///
+/// ```
+/// use rb_sys_test_helpers::{assert_ruby_class, eval, with_ruby_vm};
+///
+/// with_ruby_vm(|| {
+///     assert_ruby_class!(eval!("[1, 2, 3]"), "Array");
+///     assert_ruby_class!(eval!("1.0"), "Float", "should be a float");
+/// });
+/// ```
+#[macro_export]
+macro_rules! assert_ruby_class {
+    ($v:expr, $name:expr $(,)?) => {{
+        let actual = $crate::ruby_class_name($v);
+
+        assert!(
+            actual == $name,
+            "expected an instance of `{}`, but its class is `{}`",
+            $name,
+            actual
+        );
+    }};
+    ($v:expr, $name:expr, $($arg:tt)+) => {{
+        let actual = $crate::ruby_class_name($v);
+
+        assert!(
+            actual == $name,
+            "expected an instance of `{}`, but its class is `{}`: {}",
+            $name,
+            actual,
+            format_args!($($arg)+)
+        );
+    }};
+}
+
+/// Returns the visibility of the instance method `name` of `klass` (i.e.
+/// `"public"`), including inherited methods, or `None` if it is not defined.
+pub fn method_visibility(klass: rb_sys::VALUE, name: &str) -> Option<&'static str> {
+    unsafe {
+        let id = rb_sys::rb_intern2(name.as_ptr() as _, name.len() as _);
+        let sym = rb_sys::rb_id2sym(id);
+
+        for (predicate, visibility) in [
+            ("public_method_defined?\0", "public"),
+            ("protected_method_defined?\0", "protected"),
+            ("private_method_defined?\0", "private"),
+        ] {
+            let predicate = rb_sys::rb_intern(predicate.as_ptr() as _);
+
+            if rb_sys::special_consts::rb_test(rb_sys::rb_funcall(klass, predicate, 1, sym)) {
+                return Some(visibility);
+            }
+        }
+
+        None
+    }
+}
+
+#[doc(hidden)]
+#[track_caller]
+pub fn assert_method_visibility(klass: rb_sys::VALUE, name: &str, expected: &str) {
+    let class_name = unsafe {
+        let mut path = rb_sys::rb_class_path(klass);
+
+        rstring_to_string!(path)
+    };
+
+    match method_visibility(klass, name) {
+        Some(visibility) if visibility == expected => {}
+        Some(visibility) => panic!(
+            "expected `{}#{}` to be {}, but it is {}",
+            class_name, name, expected, visibility
+        ),
+        None => panic!(
+            "expected `{}#{}` to be defined as a {} method, but it is not defined",
+            class_name, name, expected
+        ),
+    }
+}
+
+/// Asserts that `klass` has a public instance method `name`, either its own
+/// or inherited (i.e. to check that an `Init_` function defined it). On
+/// failure, the class and method are printed. Pass the singleton class (via
+/// `rb_singleton_class`) to check for a class method.
+///
+/// ### Example
+///
+/// ```
+/// use rb_sys_test_helpers::{assert_method_defined, eval, with_ruby_vm};
+///
+/// with_ruby_vm(|| {
+///     assert_method_defined!(eval!("Array"), "push");
+/// });
+/// ```
+#[macro_export]
+macro_rules! assert_method_defined {
+    ($klass:expr, $name:expr $(,)?) => {
+        $crate::assert_method_visibility($klass, $name, "public")
+    };
+}
+
+/// Asserts that `klass` has a private instance method `name`, either its own
+/// or inherited. On failure, the class and method are printed, along with
+/// the actual visibility when the method is not private.
+///
+/// ### Example
+///
+/// ```
+/// use rb_sys_test_helpers::{assert_private_method_defined, eval, with_ruby_vm};
+///
+/// with_ruby_vm(|| {
+///     assert_private_method_defined!(eval!("Object"), "puts");
+/// });
+/// ```
+#[macro_export]
+macro_rules! assert_private_method_defined {
+    ($klass:expr, $name:expr $(,)?) => {
+        $crate::assert_method_visibility($klass, $name, "private")
+    };
+}
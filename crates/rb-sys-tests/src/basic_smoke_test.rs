@@ -23,3 +23,20 @@ fn test_global_variables_are_properly_linked() {
     unsafe { assert!(!rb_sys::rb_eArgError != 0) }
     unsafe { assert!(!rb_sys::rb_eTypeError != 0) }
 }
+
+#[ruby_test]
+fn test_check_abi_matches_linked_ruby() {
+    assert_eq!(rb_sys::check_abi(), Ok(()));
+}
+
+#[test]
+fn test_abi_mismatch_message() {
+    let err = rb_sys::AbiMismatch {
+        compiled: (3, 3),
+        runtime: (3, 4),
+    };
+
+    assert!(err
+        .to_string()
+        .starts_with("extension was compiled for Ruby 3.3, but was loaded by Ruby 3.4"));
+}
@@ -0,0 +1,290 @@
+use rb_sys::class::{
+    class_new, define_attr_accessor, define_attr_reader, define_attr_writer, define_class_under,
+    define_global_function, define_method, define_module_under, define_private_method,
+    define_protected_method, define_singleton_method, module_new, set_name, ClassBuilder,
+};
+use rb_sys::exception::protect;
+use rb_sys::object::call_method;
+use rb_sys::{
+    rb_cObject, rb_class_name, rb_class_new_instance, rb_define_class, rb_define_method,
+    rb_eArgError, rb_int2inum, rb_ivar_get, rb_ivar_set, rb_num2long, rb_obj_is_kind_of, Qfalse,
+    Qnil, Qtrue, VALUE,
+};
+use rb_sys_test_helpers::{eval, rstring_to_string, rsymbol, ruby_test};
+use std::os::raw::c_int;
+use std::ptr::null;
+
+unsafe extern "C" fn answer(_rb_self: VALUE) -> VALUE {
+    rb_int2inum(42)
+}
+
+#[ruby_test]
+fn test_class_new_subclasses_a_rust_defined_class() {
+    unsafe {
+        let base = rb_define_class("RbSysClassNewBase\0".as_ptr() as _, rb_cObject);
+        let answer: unsafe extern "C" fn(VALUE) -> VALUE = answer;
+        rb_define_method(
+            base,
+            "answer\0".as_ptr() as _,
+            Some(std::mem::transmute(answer)),
+            0,
+        );
+
+        let subclass = class_new(base);
+        let instance = rb_class_new_instance(0, null(), subclass);
+        let result = call_method(instance, "answer", &[]).unwrap();
+
+        assert_eq!(rb_obj_is_kind_of(instance, base), Qtrue as VALUE);
+        assert_eq!(rb_num2long(result), 42);
+    }
+}
+
+#[ruby_test]
+fn test_class_new_is_anonymous_until_named() {
+    unsafe {
+        let klass = class_new(rb_cObject);
+        let name = call_method(klass, "name", &[]).unwrap();
+
+        assert_eq!(name, Qnil as VALUE);
+
+        set_name(klass, None, "RbSysNamedClass");
+        let mut name = rb_class_name(klass);
+
+        assert_eq!(rstring_to_string!(name), "RbSysNamedClass");
+    }
+}
+
+#[ruby_test]
+fn test_module_new_under_namespace() {
+    unsafe {
+        let namespace = eval!("module RbSysNamespace; self; end");
+        let module = module_new();
+        set_name(module, Some(namespace), "Inner");
+        let mut name = rb_class_name(module);
+
+        assert_eq!(rstring_to_string!(name), "RbSysNamespace::Inner");
+    }
+}
+
+extern "C" fn first_arg(_rb_self: VALUE, arg: VALUE) -> VALUE {
+    arg
+}
+
+unsafe extern "C" fn count_args(argc: c_int, _argv: *const VALUE, _rb_self: VALUE) -> VALUE {
+    rb_int2inum(argc as _)
+}
+
+#[ruby_test]
+fn test_define_method_visibility() {
+    unsafe {
+        let klass = class_new(rb_cObject);
+        let first_arg: extern "C" fn(VALUE, VALUE) -> VALUE = first_arg;
+
+        define_method(klass, "public_one", first_arg);
+        define_private_method(klass, "private_one", first_arg);
+        define_protected_method(klass, "protected_one", first_arg);
+
+        let defined =
+            |predicate: &str, name: VALUE| call_method(klass, predicate, &[name]).unwrap();
+
+        assert_eq!(
+            defined("public_method_defined?", rsymbol!("public_one")),
+            Qtrue as VALUE
+        );
+        assert_eq!(
+            defined("private_method_defined?", rsymbol!("private_one")),
+            Qtrue as VALUE
+        );
+        assert_eq!(
+            defined("protected_method_defined?", rsymbol!("protected_one")),
+            Qtrue as VALUE
+        );
+        assert_eq!(
+            defined("public_method_defined?", rsymbol!("private_one")),
+            Qfalse as VALUE
+        );
+        assert_eq!(
+            defined("public_method_defined?", rsymbol!("protected_one")),
+            Qfalse as VALUE
+        );
+    }
+}
+
+#[ruby_test]
+fn test_define_method_arity() {
+    unsafe {
+        let klass = class_new(rb_cObject);
+        let first_arg: extern "C" fn(VALUE, VALUE) -> VALUE = first_arg;
+        let count_args: unsafe extern "C" fn(c_int, *const VALUE, VALUE) -> VALUE = count_args;
+
+        define_method(klass, "first_arg", first_arg);
+        define_method(klass, "count_args", count_args);
+
+        let instance = rb_class_new_instance(0, null(), klass);
+        let one = rb_int2inum(1);
+        let arity = |name: VALUE| {
+            let method = call_method(instance, "method", &[name]).unwrap();
+            rb_num2long(call_method(method, "arity", &[]).unwrap())
+        };
+
+        assert_eq!(arity(rsymbol!("first_arg")), 1);
+        assert_eq!(arity(rsymbol!("count_args")), -1);
+        assert_eq!(call_method(instance, "first_arg", &[one]).unwrap(), one);
+        assert_eq!(
+            rb_num2long(call_method(instance, "count_args", &[one, one, one]).unwrap()),
+            3
+        );
+    }
+}
+
+#[ruby_test]
+fn test_define_global_function() {
+    unsafe {
+        let first_arg: extern "C" fn(VALUE, VALUE) -> VALUE = first_arg;
+        let count_args: unsafe extern "C" fn(c_int, *const VALUE, VALUE) -> VALUE = count_args;
+
+        define_global_function("rb_sys_first_arg", first_arg);
+        define_global_function("rb_sys_count_args", count_args);
+
+        assert_eq!(rb_num2long(eval!("rb_sys_first_arg(42)")), 42);
+        assert_eq!(
+            rb_num2long(eval!(
+                "Object.new.instance_eval { rb_sys_count_args(1, 2, 3) }"
+            )),
+            3
+        );
+        assert_eq!(rb_num2long(eval!("Kernel.rb_sys_first_arg(7)")), 7);
+        assert_eq!(
+            eval!("Object.new.respond_to?(:rb_sys_first_arg)"),
+            Qfalse as VALUE
+        );
+    }
+}
+
+#[ruby_test]
+fn test_define_singleton_method() {
+    unsafe {
+        let klass = class_new(rb_cObject);
+        let first_arg: extern "C" fn(VALUE, VALUE) -> VALUE = first_arg;
+        let answer: unsafe extern "C" fn(VALUE) -> VALUE = answer;
+
+        let object = rb_class_new_instance(0, null(), klass);
+        let other = rb_class_new_instance(0, null(), klass);
+        define_singleton_method(object, "first_arg", first_arg);
+        define_singleton_method(klass, "answer", answer);
+
+        let one = rb_int2inum(1);
+        let responds =
+            |recv: VALUE, name: VALUE| call_method(recv, "respond_to?", &[name]).unwrap();
+
+        assert_eq!(call_method(object, "first_arg", &[one]).unwrap(), one);
+        assert_eq!(responds(other, rsymbol!("first_arg")), Qfalse as VALUE);
+        assert_eq!(rb_num2long(call_method(klass, "answer", &[]).unwrap()), 42);
+        assert_eq!(responds(object, rsymbol!("answer")), Qfalse as VALUE);
+    }
+}
+
+#[ruby_test]
+fn test_define_attr_accessor() {
+    unsafe {
+        let klass = class_new(rb_cObject);
+        define_attr_accessor(klass, "name");
+
+        let instance = rb_class_new_instance(0, null(), klass);
+        let ivar = rb_sys::rb_intern("@name\0".as_ptr() as _);
+        rb_ivar_set(instance, ivar, rb_int2inum(1));
+
+        assert_eq!(rb_num2long(call_method(instance, "name", &[]).unwrap()), 1);
+
+        call_method(instance, "name=", &[rb_int2inum(2)]).unwrap();
+
+        assert_eq!(rb_num2long(rb_ivar_get(instance, ivar)), 2);
+    }
+}
+
+#[ruby_test]
+fn test_define_attr_reader_and_writer() {
+    unsafe {
+        let klass = class_new(rb_cObject);
+        define_attr_reader(klass, "@reader");
+        define_attr_writer(klass, "writer");
+
+        let instance = rb_class_new_instance(0, null(), klass);
+        let responds = |name: VALUE| call_method(instance, "respond_to?", &[name]).unwrap();
+
+        assert_eq!(responds(rsymbol!("reader")), Qtrue as VALUE);
+        assert_eq!(responds(rsymbol!("reader=")), Qfalse as VALUE);
+        assert_eq!(responds(rsymbol!("writer")), Qfalse as VALUE);
+        assert_eq!(responds(rsymbol!("writer=")), Qtrue as VALUE);
+        assert_eq!(call_method(instance, "reader", &[]).unwrap(), Qnil as VALUE);
+    }
+}
+
+#[ruby_test]
+fn test_define_class_under_and_module_under() {
+    unsafe {
+        let outer = define_module_under(rb_cObject, "RbSysOuter");
+        let inner = define_class_under(outer, "Inner", rb_cObject);
+        let nested = define_module_under(inner, "Nested");
+
+        let mut inner_name = rb_class_name(inner);
+        let mut nested_name = rb_class_name(nested);
+
+        assert_eq!(rstring_to_string!(inner_name), "RbSysOuter::Inner");
+        assert_eq!(rstring_to_string!(nested_name), "RbSysOuter::Inner::Nested");
+        assert_eq!(eval!("RbSysOuter::Inner.superclass"), rb_cObject);
+        assert_eq!(define_class_under(outer, "Inner", rb_cObject), inner);
+        assert_eq!(define_module_under(rb_cObject, "RbSysOuter"), outer);
+    }
+}
+
+#[ruby_test]
+fn test_define_class_under_with_nul_byte() {
+    unsafe {
+        let outer = define_module_under(rb_cObject, "RbSysNulOuter");
+        let err = protect(|| define_class_under(outer, "In\0ner", rb_cObject)).unwrap_err();
+
+        assert_eq!(err.class(), rb_eArgError);
+    }
+}
+
+unsafe extern "C" fn add(_rb_self: VALUE, a: VALUE, b: VALUE) -> VALUE {
+    rb_int2inum((rb_num2long(a) + rb_num2long(b)) as _)
+}
+
+unsafe extern "C" fn create(klass: VALUE) -> VALUE {
+    rb_class_new_instance(0, null(), klass)
+}
+
+#[ruby_test]
+fn test_class_builder() {
+    unsafe {
+        let add: unsafe extern "C" fn(VALUE, VALUE, VALUE) -> VALUE = add;
+        let create: unsafe extern "C" fn(VALUE) -> VALUE = create;
+
+        let klass = ClassBuilder::define("RbSysBuiltClass", rb_cObject)
+            .method("add", add)
+            .singleton_method("create", create)
+            .constant("ANSWER", rb_int2inum(42))
+            .attr_accessor("name")
+            .build();
+
+        assert_eq!(eval!("RbSysBuiltClass"), klass);
+        assert_eq!(rb_num2long(eval!("RbSysBuiltClass.create.add(1, 2)")), 3);
+        assert_eq!(rb_num2long(eval!("RbSysBuiltClass::ANSWER")), 42);
+
+        let mut name = eval!("o = RbSysBuiltClass.create; o.name = 'ferris'; o.name");
+
+        assert_eq!(rstring_to_string!(name), "ferris");
+    }
+}
+
+#[ruby_test]
+fn test_class_builder_with_nul_byte() {
+    unsafe {
+        let builder = ClassBuilder::new(class_new(rb_cObject));
+        let err = protect(|| builder.attr_reader("na\0me")).unwrap_err();
+
+        assert_eq!(err.class(), rb_eArgError);
+    }
+}
@@ -0,0 +1,117 @@
+use rb_sys::exception::{catch_throw, ensure, exception_new, protect, raise_exception, throw};
+use rb_sys::object::is_kind_of;
+use rb_sys::{
+    rb_cObject, rb_eArgError, rb_eStandardError, rb_eval_string, rb_int2inum, rb_num2long,
+    rb_obj_alloc, rb_obj_class, Qnil, VALUE,
+};
+use std::cell::Cell;
+
+#[rb_sys_test_helpers::ruby_test]
+fn test_ensure_runs_cleanup_when_body_raises() {
+    let cleaned_up = Cell::new(false);
+
+    let result = unsafe {
+        protect(|| {
+            ensure(
+                || rb_eval_string("raise 'oh no'\0".as_ptr() as _),
+                || cleaned_up.set(true),
+            )
+        })
+    };
+
+    let err = result.unwrap_err();
+
+    assert!(cleaned_up.get());
+    assert_eq!(unsafe { err.message() }.as_deref(), Some("oh no"));
+}
+
+#[rb_sys_test_helpers::ruby_test]
+fn test_ensure_runs_cleanup_and_returns_body_value() {
+    let cleaned_up = Cell::new(false);
+    let result = unsafe { ensure(|| 42, || cleaned_up.set(true)) };
+
+    assert!(cleaned_up.get());
+    assert_eq!(result, 42);
+}
+
+#[rb_sys_test_helpers::ruby_test]
+fn test_ensure_resumes_panics_after_cleanup() {
+    let cleaned_up = Cell::new(false);
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| unsafe {
+        ensure(|| panic!("boom"), || cleaned_up.set(true))
+    }));
+
+    assert!(result.is_err());
+    assert!(cleaned_up.get());
+}
+
+#[rb_sys_test_helpers::ruby_test]
+fn test_raise_exception_with_custom_class() {
+    unsafe {
+        let class = rb_eval_string(
+            "class RbSysCustomError < StandardError; end; RbSysCustomError\0".as_ptr() as _,
+        );
+        let err = protect(|| raise_exception(class, "100% broken, see \"%s\"")).unwrap_err();
+
+        assert_eq!(err.class(), class);
+        assert!(is_kind_of(err.value(), rb_eStandardError));
+        assert_eq!(err.message().as_deref(), Some("100% broken, see \"%s\""));
+    }
+}
+
+#[rb_sys_test_helpers::ruby_test]
+fn test_exception_new_does_not_raise() {
+    unsafe {
+        let exc = exception_new(rb_eStandardError, "not raised");
+
+        assert_eq!(rb_obj_class(exc), rb_eStandardError);
+    }
+}
+
+#[rb_sys_test_helpers::ruby_test]
+fn test_catch_throw_returns_thrown_value() {
+    unsafe {
+        let tag = rb_obj_alloc(rb_cObject);
+        let result = catch_throw(tag, |tag| {
+            throw(tag, rb_int2inum(42));
+        });
+
+        assert_eq!(rb_num2long(result), 42);
+    }
+}
+
+#[rb_sys_test_helpers::ruby_test]
+fn test_catch_throw_returns_body_value_and_passes_other_tags() {
+    unsafe {
+        let outer = rb_obj_alloc(rb_cObject);
+        let inner = rb_obj_alloc(rb_cObject);
+
+        assert_eq!(catch_throw(inner, |_| Qnil as VALUE), Qnil as VALUE);
+
+        let result = catch_throw(outer, |outer| {
+            catch_throw(inner, |_| throw(outer, rb_int2inum(1)));
+            rb_int2inum(2)
+        });
+
+        assert_eq!(rb_num2long(result), 1);
+    }
+}
+
+#[rb_sys_test_helpers::ruby_test]
+fn test_throw_without_catch_raises() {
+    unsafe {
+        let tag = rb_obj_alloc(rb_cObject);
+        let err = protect(|| throw(tag, Qnil as VALUE)).unwrap_err();
+
+        assert!(is_kind_of(err.value(), rb_eArgError));
+    }
+}
+
+#[rb_sys_test_helpers::ruby_test]
+fn test_catch_throw_resumes_panics() {
+    let result = std::panic::catch_unwind(|| unsafe {
+        catch_throw(rb_obj_alloc(rb_cObject), |_| panic!("boom"))
+    });
+
+    assert!(result.is_err());
+}
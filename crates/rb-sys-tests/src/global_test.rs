@@ -0,0 +1,40 @@
+use rb_sys::exception::protect;
+use rb_sys::global::{gvar_get, gvar_set};
+use rb_sys::{rb_eArgError, Qnil, VALUE};
+use rb_sys_test_helpers::{eval, rstring, rstring_to_string, ruby_test};
+
+#[ruby_test]
+fn test_gvar_set_and_get() {
+    unsafe {
+        gvar_set("$rb_sys_test_global", rstring!("hello"));
+        let mut value = gvar_get("$rb_sys_test_global");
+
+        assert_eq!(rstring_to_string!(value), "hello");
+
+        let mut from_ruby = eval!("$rb_sys_test_global + ' world'");
+
+        assert_eq!(rstring_to_string!(from_ruby), "hello world");
+    }
+}
+
+#[ruby_test]
+fn test_gvar_dollar_prefix_is_optional() {
+    unsafe {
+        gvar_set("rb_sys_test_unprefixed", rstring!("hi"));
+        let mut value = gvar_get("$rb_sys_test_unprefixed");
+
+        assert_eq!(rstring_to_string!(value), "hi");
+    }
+}
+
+#[ruby_test]
+fn test_gvar_get_undefined_is_nil() {
+    assert_eq!(unsafe { gvar_get("$rb_sys_never_defined") }, Qnil as VALUE);
+}
+
+#[ruby_test]
+fn test_gvar_get_raises_on_nul_byte() {
+    let err = unsafe { protect(|| gvar_get("$oh\0no")) }.unwrap_err();
+
+    assert_eq!(unsafe { err.class() }, unsafe { rb_eArgError });
+}
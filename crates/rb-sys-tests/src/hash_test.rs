@@ -0,0 +1,115 @@
+use rb_sys::exception::protect;
+use rb_sys::hash::{hash_from_pairs, hash_lookup, hash_merge, hash_merge_with, hash_new_capa};
+use rb_sys::{
+    rb_eFrozenError, rb_equal, rb_hash_aref, rb_hash_size, rb_int2inum, rb_num2long, Qnil, Qtrue,
+    VALUE,
+};
+use rb_sys_test_helpers::{eval, rstring, ruby_test};
+
+#[ruby_test]
+fn test_hash_new_capa_is_empty() {
+    unsafe {
+        let hash = hash_new_capa(128);
+
+        assert_eq!(rb_num2long(rb_hash_size(hash)), 0);
+    }
+}
+
+#[ruby_test(gc_stress)]
+fn test_hash_from_pairs() {
+    unsafe {
+        let pairs: Vec<[VALUE; 2]> = (0..64)
+            .map(|i| [rb_int2inum(i), rb_int2inum(i * 2)])
+            .collect();
+        let hash = hash_from_pairs(&pairs);
+
+        assert_eq!(rb_num2long(rb_hash_size(hash)), 64);
+
+        for i in 0..64 {
+            let value = rb_hash_aref(hash, rb_int2inum(i));
+            assert_eq!(rb_num2long(value), (i * 2) as _);
+        }
+
+        assert_eq!(rb_hash_aref(hash, rb_int2inum(64)), Qnil as VALUE);
+    }
+}
+
+#[ruby_test]
+fn test_hash_from_pairs_last_duplicate_wins() {
+    unsafe {
+        let key = rstring!("key");
+        let hash = hash_from_pairs(&[[key, rb_int2inum(1)], [key, rb_int2inum(2)]]);
+
+        assert_eq!(rb_num2long(rb_hash_size(hash)), 1);
+        assert_eq!(rb_num2long(rb_hash_aref(hash, key)), 2);
+    }
+}
+
+#[ruby_test]
+fn test_hash_lookup_distinguishes_nil_from_missing() {
+    unsafe {
+        let present = rstring!("present");
+        let nil_key = rstring!("nil");
+        let missing = rstring!("missing");
+        let hash = hash_from_pairs(&[[present, rb_int2inum(1)], [nil_key, Qnil as VALUE]]);
+
+        assert_eq!(hash_lookup(hash, present), Some(rb_int2inum(1)));
+        assert_eq!(hash_lookup(hash, nil_key), Some(Qnil as VALUE));
+        assert_eq!(hash_lookup(hash, missing), None);
+        assert_eq!(rb_hash_aref(hash, missing), Qnil as VALUE);
+    }
+}
+
+#[ruby_test]
+fn test_hash_merge_last_wins() {
+    unsafe {
+        let dest = eval!("{ a: 1, b: 2 }");
+        let merged = hash_merge(dest, eval!("{ b: 3, c: 4 }"));
+
+        assert_eq!(merged, dest);
+        assert_eq!(
+            rb_equal(dest, eval!("{ a: 1, b: 3, c: 4 }")),
+            Qtrue as VALUE
+        );
+    }
+}
+
+#[ruby_test(gc_stress)]
+fn test_hash_merge_with_resolves_conflicts() {
+    unsafe {
+        let dest = eval!("{ a: 1, b: 2 }");
+        let mut conflicts = vec![];
+
+        hash_merge_with(dest, eval!("{ b: 3, c: 4 }"), |key, old, new| {
+            conflicts.push(key);
+            rb_int2inum((rb_num2long(old) + rb_num2long(new)) as _)
+        });
+
+        assert_eq!(conflicts, [eval!(":b")]);
+        assert_eq!(
+            rb_equal(dest, eval!("{ a: 1, b: 5, c: 4 }")),
+            Qtrue as VALUE
+        );
+    }
+}
+
+#[ruby_test]
+fn test_hash_merge_with_frozen_dest() {
+    unsafe {
+        let dest = eval!("{ a: 1 }.freeze");
+        let err = protect(|| hash_merge_with(dest, eval!("{}"), |_, _, new| new)).unwrap_err();
+
+        assert_eq!(err.class(), rb_eFrozenError);
+    }
+}
+
+#[ruby_test]
+fn test_hash_merge_with_resumes_panics() {
+    let result = std::panic::catch_unwind(|| unsafe {
+        hash_merge_with(eval!("{ a: 1 }"), eval!("{ a: 2 }"), |_, _, _| {
+            panic!("conflict")
+        })
+    });
+
+    assert!(result.is_err());
+}
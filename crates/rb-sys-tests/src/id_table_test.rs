@@ -0,0 +1,68 @@
+use rb_sys::id_table::IdTable;
+use rb_sys::symbol::intern;
+use rb_sys::{rb_cObject, rb_data_type_t, rb_data_typed_object_wrap, rb_gc, rb_int2inum, VALUE};
+use rb_sys_test_helpers::{rstring, rstring_to_string, ruby_test};
+use std::os::raw::c_void;
+
+#[ruby_test]
+fn test_insert_and_lookup_by_id() {
+    unsafe {
+        let mut table = IdTable::new();
+        let one = rb_int2inum(1);
+        let two = rb_int2inum(2);
+
+        assert!(table.is_empty());
+        assert_eq!(table.insert(intern("one"), one), None);
+        assert_eq!(table.insert(intern("two"), one), None);
+        assert_eq!(table.insert(intern("two"), two), Some(one));
+
+        assert_eq!(table.len(), 2);
+        assert_eq!(table.get(intern("one")), Some(one));
+        assert_eq!(table.get(intern("two")), Some(two));
+        assert_eq!(table.get(intern("three")), None);
+
+        assert_eq!(table.remove(intern("one")), Some(one));
+        assert_eq!(table.remove(intern("one")), None);
+        assert!(!table.contains(intern("one")));
+        assert_eq!(table.len(), 1);
+        assert!(table.memsize() > 0);
+    }
+}
+
+unsafe extern "C" fn mark_table(data: *mut c_void) {
+    (*(data as *const IdTable)).mark();
+}
+
+unsafe extern "C" fn free_table(data: *mut c_void) {
+    drop(Box::from_raw(data as *mut IdTable));
+}
+
+#[ruby_test(gc_stress)]
+fn test_mark_keeps_values_alive() {
+    unsafe {
+        let data_type: &'static mut rb_data_type_t = Box::leak(Box::new(std::mem::zeroed()));
+        data_type.wrap_struct_name = "RbSysIdTableTest\0".as_ptr() as _;
+        data_type.function.dmark = Some(mark_table);
+        data_type.function.dfree = Some(free_table);
+
+        let table = Box::into_raw(Box::new(IdTable::new()));
+        let obj = rb_data_typed_object_wrap(rb_cObject, table as _, data_type);
+
+        for i in 0..32 {
+            (*table).insert(
+                intern(&format!("key_{}", i)),
+                rstring!(format!("value {}", i)),
+            );
+        }
+
+        rb_gc();
+
+        for i in 0..32 {
+            let mut value: VALUE = (*table).get(intern(&format!("key_{}", i))).unwrap();
+
+            assert_eq!(rstring_to_string!(value), format!("value {}", i));
+        }
+
+        let _ = rb_sys::rb_gc_guard!(obj);
+    }
+}
@@ -11,6 +11,12 @@ mod ruby_macros_test;
 #[cfg(test)]
 mod value_type_test;
 
+#[cfg(test)]
+mod class_test;
+
+#[cfg(test)]
+mod scan_args_test;
+
 #[cfg(test)]
 mod special_consts_test;
 
@@ -20,8 +26,44 @@ mod tracking_allocator_test;
 #[cfg(all(test, unix))]
 mod memory_test;
 
+#[cfg(all(test, ruby_have_ruby_memory_view_h))]
+mod memory_view_test;
+
+#[cfg(test)]
+mod exception_test;
+
+#[cfg(test)]
+mod global_test;
+
+#[cfg(test)]
+mod hash_test;
+
+#[cfg(all(test, ruby_gte_2_7))]
+mod id_table_test;
+
+#[cfg(test)]
+mod numeric_test;
+
+#[cfg(test)]
+mod object_test;
+
+#[cfg(test)]
+mod proc_test;
+
+#[cfg(all(test, ruby_have_ruby_ractor_h))]
+mod ractor_test;
+
+#[cfg(all(test, ruby_have_ruby_fiber_scheduler_h))]
+mod scheduler_test;
+
 #[cfg(test)]
 mod stable_api_test;
 
+#[cfg(test)]
+mod string_test;
+
+#[cfg(test)]
+mod typed_data_test;
+
 #[cfg(test)]
 mod symbol_test;
@@ -1,6 +1,14 @@
+use rb_sys::class::class_new;
+use rb_sys::memory::{adjust_memory, mark_slice, register_mark_object, size_delta};
 use rb_sys::VALUE;
-use rb_sys::{rb_gc_guard, rb_str_cat_cstr, rb_str_new_cstr, RSTRING_PTR};
-use rb_sys_test_helpers::{rstring_to_string, ruby_test};
+use rb_sys::{
+    rb_cObject, rb_class_new_instance, rb_data_type_t, rb_data_typed_object_wrap, rb_gc,
+    rb_gc_guard, rb_obj_class, rb_str_cat_cstr, rb_str_new_cstr, rb_utf8_str_new, ruby_value_type,
+    RB_TYPE_P, RSTRING_PTR,
+};
+use rb_sys_test_helpers::{capture_gc_stat_for, rstring_to_string, ruby_test};
+use std::ffi::c_void;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 #[ruby_test(gc_stress)]
 fn test_rb_gc_guarded_ptr_basic() {
@@ -54,3 +62,90 @@ fn test_rb_gc_guarded_ptr_vec() {
         }
     }
 }
+
+unsafe extern "C" fn mark_values(data: *mut c_void) {
+    mark_slice(&*(data as *const Vec<VALUE>));
+}
+
+unsafe extern "C" fn free_values(data: *mut c_void) {
+    drop(Box::from_raw(data as *mut Vec<VALUE>));
+}
+
+#[ruby_test(gc_stress)]
+fn test_mark_slice_keeps_values_alive() {
+    unsafe {
+        let data_type: &'static mut rb_data_type_t = Box::leak(Box::new(std::mem::zeroed()));
+        data_type.wrap_struct_name = "RbSysMarkSliceTest\0".as_ptr() as _;
+        data_type.function.dmark = Some(mark_values);
+        data_type.function.dfree = Some(free_values);
+
+        let values = Box::into_raw(Box::<Vec<VALUE>>::default());
+        let obj = rb_data_typed_object_wrap(rb_cObject, values as _, data_type);
+
+        for i in 0..32 {
+            let s = format!("value {}", i);
+            let value = rb_utf8_str_new(s.as_ptr() as _, s.len() as _);
+            (*values).push(value);
+        }
+
+        rb_gc();
+
+        for (i, value) in (*values).iter_mut().enumerate() {
+            assert_eq!(rstring_to_string!(*value), format!("value {}", i));
+        }
+
+        let _ = rb_gc_guard!(obj);
+    }
+}
+
+#[test]
+fn test_size_delta_large_values() {
+    assert_eq!(size_delta(0, 0), 0);
+    assert_eq!(size_delta(usize::MAX, usize::MAX), 0);
+    assert_eq!(size_delta(0, isize::MAX as usize), isize::MAX);
+    assert_eq!(size_delta(isize::MAX as usize, 0), -isize::MAX);
+    assert_eq!(size_delta(0, usize::MAX), isize::MAX);
+    assert_eq!(size_delta(usize::MAX, 0), isize::MIN);
+    assert_eq!(size_delta(usize::MAX, usize::MAX - 1), -1);
+    assert_eq!(size_delta(1, usize::MAX), isize::MAX);
+}
+
+#[ruby_test]
+fn test_adjust_memory() {
+    let (reported, increased) =
+        capture_gc_stat_for!("malloc_increase_bytes", { adjust_memory(4096) });
+
+    assert_eq!(reported, 4096);
+    assert_eq!(increased, 4096);
+    assert_eq!(adjust_memory(0), 0);
+
+    adjust_memory(-4096);
+}
+
+// Stores the class with its bits flipped, so the conservative GC does not
+// find a reference to it.
+static REGISTERED_CLASS: AtomicUsize = AtomicUsize::new(0);
+
+#[inline(never)]
+unsafe fn register_class() {
+    let klass = register_mark_object(class_new(rb_cObject));
+
+    REGISTERED_CLASS.store(!(klass as usize), Ordering::SeqCst);
+}
+
+#[ruby_test(gc_stress)]
+fn test_register_mark_object_survives_gc() {
+    unsafe {
+        register_class();
+
+        for _ in 0..3 {
+            rb_gc();
+        }
+
+        let klass = !REGISTERED_CLASS.load(Ordering::SeqCst) as VALUE;
+        let instance = rb_class_new_instance(0, std::ptr::null(), klass);
+
+        assert!(RB_TYPE_P(klass, ruby_value_type::RUBY_T_CLASS));
+        assert_eq!(rb_obj_class(instance), klass);
+    }
+}
@@ -0,0 +1,56 @@
+use rb_sys::class::class_new;
+use rb_sys::memory_view::{available, export_bytes_mut, register, MemoryView};
+use rb_sys::{
+    rb_cObject, rb_class_new_instance, rb_int2inum, rb_memory_view_entry_t, rb_memory_view_t, VALUE,
+};
+use rb_sys_test_helpers::ruby_test;
+use std::os::raw::c_int;
+use std::ptr::null;
+
+static mut BUFFER: [u8; 4] = [1, 2, 3, 4];
+
+unsafe extern "C" fn get(obj: VALUE, view: *mut rb_memory_view_t, _flags: c_int) -> bool {
+    export_bytes_mut(view, obj, &mut *std::ptr::addr_of_mut!(BUFFER))
+}
+
+unsafe extern "C" fn release(_obj: VALUE, _view: *mut rb_memory_view_t) -> bool {
+    true
+}
+
+unsafe extern "C" fn is_available(_obj: VALUE) -> bool {
+    true
+}
+
+static ENTRY: rb_memory_view_entry_t = rb_memory_view_entry_t {
+    get_func: Some(get),
+    release_func: Some(release),
+    available_p_func: Some(is_available),
+};
+
+#[ruby_test]
+fn test_memory_view_round_trip() {
+    unsafe {
+        let klass = class_new(rb_cObject);
+        assert!(register(klass, &ENTRY));
+        assert!(!register(klass, &ENTRY));
+
+        let obj = rb_class_new_instance(0, null(), klass);
+        assert!(available(obj));
+        assert!(!available(rb_int2inum(1)));
+        assert!(MemoryView::get(rb_int2inum(1), false).is_none());
+
+        let mut view = MemoryView::get(obj, true).expect("no memory view");
+        assert_eq!(view.obj(), obj);
+        assert_eq!(view.as_bytes(), [1, 2, 3, 4]);
+        assert_eq!(view.len(), 4);
+        assert_eq!(view.item_size(), 1);
+        assert_eq!(view.ndim(), 1);
+        assert!(!view.is_readonly());
+
+        view.as_bytes_mut().unwrap()[0] = 42;
+        drop(view);
+
+        let view = MemoryView::get(obj, false).expect("no memory view");
+        assert_eq!(view.as_bytes(), [42, 2, 3, 4]);
+    }
+}
@@ -0,0 +1,185 @@
+use rb_sys::exception::protect;
+use rb_sys::numeric::{
+    f64_to_ruby, integer_from_le_bytes, integer_from_string, integer_to_le_bytes,
+    integer_to_string, to_f64,
+};
+use rb_sys::{rb_eArgError, rb_eTypeError, rb_equal, rb_int2inum, Qnil, Qtrue, VALUE};
+use rb_sys_test_helpers::{eval, rstring, ruby_test};
+
+#[ruby_test]
+fn test_integer_to_le_bytes_small() {
+    unsafe {
+        assert_eq!(integer_to_le_bytes(rb_int2inum(0)), [0x00]);
+        assert_eq!(integer_to_le_bytes(rb_int2inum(1)), [0x01]);
+        assert_eq!(integer_to_le_bytes(rb_int2inum(-1)), [0xff]);
+        assert_eq!(integer_to_le_bytes(rb_int2inum(127)), [0x7f]);
+        assert_eq!(integer_to_le_bytes(rb_int2inum(128)), [0x80, 0x00]);
+        assert_eq!(integer_to_le_bytes(rb_int2inum(-128)), [0x80]);
+        assert_eq!(integer_to_le_bytes(rb_int2inum(-129)), [0x7f, 0xff]);
+        assert_eq!(integer_to_le_bytes(rb_int2inum(0x1234)), [0x34, 0x12]);
+    }
+}
+
+#[ruby_test]
+fn test_integer_to_le_bytes_matches_i128() {
+    unsafe {
+        let value = eval!("2**100 + 12345");
+        let expected = ((1i128 << 100) + 12345).to_le_bytes();
+
+        assert_eq!(integer_to_le_bytes(value), expected[..13]);
+
+        let value = eval!("-(2**100)");
+        let expected = (-(1i128 << 100)).to_le_bytes();
+
+        assert_eq!(integer_to_le_bytes(value), expected[..13]);
+    }
+}
+
+#[ruby_test(gc_stress)]
+fn test_integer_le_bytes_round_trip() {
+    unsafe {
+        for value in [
+            eval!("0"),
+            eval!("-1"),
+            eval!("2**64"),
+            eval!("-(2**64)"),
+            eval!("2**127 - 1"),
+            eval!("-(2**127)"),
+            eval!("3**200"),
+            eval!("-(7**150)"),
+        ] {
+            let bytes = integer_to_le_bytes(value);
+            let round_tripped = integer_from_le_bytes(&bytes);
+
+            assert_eq!(rb_equal(value, round_tripped), Qtrue as VALUE);
+        }
+    }
+}
+
+#[ruby_test]
+fn test_integer_from_le_bytes_empty_is_zero() {
+    unsafe {
+        let value = integer_from_le_bytes(&[]);
+
+        assert_eq!(rb_equal(value, rb_int2inum(0)), Qtrue as VALUE);
+    }
+}
+
+#[ruby_test]
+fn test_integer_from_le_bytes_is_signed() {
+    unsafe {
+        let value = integer_from_le_bytes(&[0xff; 16]);
+        assert_eq!(rb_equal(value, rb_int2inum(-1)), Qtrue as VALUE);
+
+        let value = integer_from_le_bytes(&[0xff, 0x00]);
+        assert_eq!(rb_equal(value, rb_int2inum(255)), Qtrue as VALUE);
+    }
+}
+
+#[ruby_test]
+fn test_integer_to_le_bytes_raises_for_non_integer() {
+    let err = unsafe { protect(|| integer_to_le_bytes(rstring!("1"))) }.unwrap_err();
+
+    assert_eq!(unsafe { err.class() }, unsafe { rb_eTypeError });
+}
+
+#[ruby_test]
+fn test_to_f64() {
+    unsafe {
+        assert_eq!(to_f64(rb_int2inum(42)), 42.0);
+        assert_eq!(to_f64(rb_int2inum(-7)), -7.0);
+        assert_eq!(to_f64(eval!("2**70")), 2f64.powi(70));
+        assert_eq!(to_f64(eval!("3/4r")), 0.75);
+        assert_eq!(to_f64(eval!("1.5")), 1.5);
+        assert_eq!(to_f64(eval!("-0.1")), -0.1);
+        assert!(to_f64(eval!("Float::NAN")).is_nan());
+        assert_eq!(to_f64(eval!("-Float::INFINITY")), f64::NEG_INFINITY);
+    }
+}
+
+#[ruby_test]
+fn test_to_f64_raises_for_non_numeric() {
+    for value in [rstring!("1.5"), Qnil as VALUE] {
+        let err = unsafe { protect(|| to_f64(value)) }.unwrap_err();
+
+        assert_eq!(unsafe { err.class() }, unsafe { rb_eTypeError });
+    }
+}
+
+#[ruby_test(gc_stress)]
+fn test_f64_to_ruby_round_trip() {
+    unsafe {
+        for value in [
+            0.0,
+            -0.0,
+            1.5,
+            -2.25,
+            1e300,
+            f64::MIN_POSITIVE,
+            f64::INFINITY,
+        ] {
+            let float = f64_to_ruby(value);
+
+            assert_eq!(to_f64(float).to_bits(), value.to_bits());
+        }
+
+        assert_eq!(rb_equal(f64_to_ruby(0.5), eval!("0.5")), Qtrue as VALUE);
+    }
+}
+
+#[ruby_test]
+fn test_integer_string_round_trip_base_10() {
+    unsafe {
+        for digits in [
+            "0",
+            "42",
+            "-42",
+            "340282366920938463463374607431768211456",
+            "-99999999999999999999999",
+        ] {
+            let value = integer_from_string(digits, 10);
+
+            assert_eq!(integer_to_string(value, 10), digits);
+        }
+
+        let expected = eval!("2**128");
+        let parsed = integer_from_string("340282366920938463463374607431768211456", 10);
+        assert_eq!(rb_equal(parsed, expected), Qtrue as VALUE);
+    }
+}
+
+#[ruby_test]
+fn test_integer_string_round_trip_base_16() {
+    unsafe {
+        assert_eq!(integer_to_string(rb_int2inum(255), 16), "ff");
+        assert_eq!(
+            integer_to_string(eval!("-(2**64)"), 16),
+            "-10000000000000000"
+        );
+
+        for digits in ["ff", "-ff", "deadbeefdeadbeefdeadbeefdeadbeef"] {
+            let value = integer_from_string(digits, 16);
+
+            assert_eq!(integer_to_string(value, 16), digits);
+        }
+
+        let prefixed = integer_from_string("0xff", 16);
+        assert_eq!(rb_equal(prefixed, rb_int2inum(255)), Qtrue as VALUE);
+    }
+}
+
+#[ruby_test]
+fn test_integer_from_string_invalid() {
+    unsafe {
+        let err = protect(|| integer_from_string("12abc", 10)).unwrap_err();
+        assert_eq!(err.class(), rb_eArgError);
+
+        let err = protect(|| integer_to_string(rb_int2inum(1), 37)).unwrap_err();
+        assert_eq!(err.class(), rb_eArgError);
+
+        for base in [0, 1, 37, u32::MAX] {
+            let err = protect(|| integer_from_string("10", base)).unwrap_err();
+            assert_eq!(err.class(), rb_eArgError);
+        }
+    }
+}
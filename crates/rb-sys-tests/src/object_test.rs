@@ -0,0 +1,358 @@
+use rb_sys::exception::protect;
+use rb_sys::object::{
+    call_super, check_frozen, deep_eql, freeze, instance_variables, is_instance_of, is_kind_of,
+    is_tainted, method_arity, obj_alloc, obj_as_string, obj_dup, raise_no_method_error, taint,
+    try_call, typed_data_wrap, untaint, FrozenValue,
+};
+use rb_sys::{
+    rb_cInteger, rb_cObject, rb_check_typeddata, rb_data_type_t, rb_eFrozenError, rb_eRuntimeError,
+    rb_eTypeError, rb_int2inum, rb_mComparable, rb_num2long, rb_obj_frozen_p, rb_str_cat, Qnil,
+    Qtrue, RSTRING_LEN, VALUE,
+};
+use rb_sys_test_helpers::{eval, rstring, rstring_to_string, ruby_test};
+
+#[ruby_test]
+fn test_try_call_when_receiver_responds() {
+    let string = rstring!("hello");
+    let size = unsafe { try_call(string, "size", &[]) }.unwrap();
+
+    assert_eq!(unsafe { rb_num2long(size.unwrap()) }, 5);
+}
+
+#[ruby_test]
+fn test_try_call_when_receiver_does_not_respond() {
+    let obj = eval!("Object.new");
+    let result = unsafe { try_call(obj, "size", &[]) }.unwrap();
+
+    assert_eq!(result, None);
+}
+
+#[ruby_test]
+fn test_try_call_passes_arguments() {
+    let string = rstring!("hello");
+    let other = rstring!(" world");
+    let mut result = unsafe { try_call(string, "+", &[other]) }.unwrap().unwrap();
+
+    assert_eq!(unsafe { rstring_to_string!(result) }, "hello world");
+}
+
+#[ruby_test]
+fn test_try_call_captures_exceptions() {
+    let obj = eval!("Class.new { def boom; raise 'oh no'; end }.new");
+    let err = unsafe { try_call(obj, "boom", &[]) }.unwrap_err();
+
+    assert_eq!(unsafe { err.message() }.as_deref(), Some("oh no"));
+}
+
+#[ruby_test]
+fn test_frozen_value_rejects_unfrozen_objects() {
+    let string = rstring!("hello");
+
+    assert_eq!(unsafe { FrozenValue::new(string) }, None);
+}
+
+#[ruby_test]
+fn test_freeze_returns_frozen_value() {
+    unsafe {
+        let string = rstring!("hello");
+        let frozen = freeze(string);
+
+        assert_eq!(frozen.get(), string);
+        assert_eq!(rb_obj_frozen_p(string), Qtrue as VALUE);
+        assert_eq!(FrozenValue::new(string), Some(frozen));
+    }
+}
+
+#[ruby_test]
+fn test_frozen_value_accepts_immediates() {
+    unsafe {
+        assert!(FrozenValue::new(Qnil as VALUE).is_some());
+        assert!(FrozenValue::new(rb_int2inum(42)).is_some());
+    }
+}
+
+#[ruby_test]
+fn test_obj_as_string() {
+    unsafe {
+        let mut string = obj_as_string(rb_int2inum(42)).unwrap();
+        assert_eq!(rstring_to_string!(string), "42");
+
+        let mut string = obj_as_string(eval!(":hello")).unwrap();
+        assert_eq!(rstring_to_string!(string), "hello");
+
+        let mut string = obj_as_string(Qnil as VALUE).unwrap();
+        assert_eq!(rstring_to_string!(string), "");
+
+        let original = rstring!("same");
+        assert_eq!(obj_as_string(original).unwrap(), original);
+    }
+}
+
+#[ruby_test]
+fn test_obj_as_string_when_to_s_raises() {
+    let obj = eval!("Class.new { def to_s; raise 'no string for you'; end }.new");
+    let err = unsafe { obj_as_string(obj) }.unwrap_err();
+
+    assert_eq!(unsafe { err.class() }, unsafe { rb_eRuntimeError });
+    assert_eq!(
+        unsafe { err.message() }.as_deref(),
+        Some("no string for you")
+    );
+}
+
+#[ruby_test]
+fn test_check_frozen_on_unfrozen_object() {
+    let string = rstring!("hello");
+
+    assert!(unsafe { protect(|| check_frozen(string)) }.is_ok());
+}
+
+#[ruby_test]
+fn test_check_frozen_raises_before_mutation() {
+    let string = rstring!("hello");
+    unsafe { freeze(string) };
+
+    let err = unsafe {
+        protect(|| {
+            check_frozen(string);
+            rb_str_cat(string, "!".as_ptr() as _, 1);
+        })
+    }
+    .unwrap_err();
+
+    assert_eq!(unsafe { err.class() }, unsafe { rb_eFrozenError });
+    assert_eq!(unsafe { RSTRING_LEN(string) }, 5);
+}
+
+#[ruby_test]
+fn test_is_instance_of_vs_is_kind_of() {
+    unsafe {
+        let base = eval!("class RbSysKindBase; end; RbSysKindBase");
+        let sub = eval!("class RbSysKindSub < RbSysKindBase; end; RbSysKindSub");
+        let instance = eval!("RbSysKindSub.new");
+
+        assert!(is_instance_of(instance, sub));
+        assert!(is_kind_of(instance, sub));
+
+        assert!(!is_instance_of(instance, base));
+        assert!(is_kind_of(instance, base));
+
+        assert!(!is_instance_of(instance, rb_cObject));
+        assert!(is_kind_of(instance, rb_cObject));
+    }
+}
+
+#[ruby_test]
+fn test_is_kind_of_included_module() {
+    unsafe {
+        let one = rb_int2inum(1);
+
+        assert!(is_instance_of(one, rb_cInteger));
+        assert!(is_kind_of(one, rb_mComparable));
+        assert!(!is_instance_of(one, rb_mComparable));
+    }
+}
+
+#[ruby_test]
+fn test_is_instance_of_raises_for_non_class() {
+    let err =
+        unsafe { protect(|| is_instance_of(rb_int2inum(1), rstring!("Integer"))) }.unwrap_err();
+
+    assert_eq!(unsafe { err.class() }, unsafe { rb_eTypeError });
+}
+
+#[ruby_test]
+fn test_instance_variables() {
+    unsafe {
+        let obj = eval!(
+            "o = Object.new; o.instance_variable_set(:@name, 'a'); o.instance_variable_set(:@size, 1); o.instance_variable_set(:@émoji, nil); o"
+        );
+
+        assert_eq!(instance_variables(obj), ["@name", "@size", "@émoji"]);
+        assert!(instance_variables(eval!("Object.new")).is_empty());
+    }
+}
+
+#[ruby_test]
+fn test_method_arity() {
+    unsafe {
+        let obj = eval!(
+            "Class.new { def fixed(a, b); end; def optional(a, b = 1); end; def splat(*args); end; def none; end; private def secret(a); end }.new"
+        );
+
+        assert_eq!(method_arity(obj, "fixed").unwrap(), 2);
+        assert_eq!(method_arity(obj, "optional").unwrap(), -2);
+        assert_eq!(method_arity(obj, "splat").unwrap(), -1);
+        assert_eq!(method_arity(obj, "none").unwrap(), 0);
+        assert_eq!(method_arity(obj, "secret").unwrap(), 1);
+    }
+}
+
+#[ruby_test]
+fn test_method_arity_of_missing_method() {
+    unsafe {
+        let err = method_arity(eval!("Object.new"), "does_not_exist").unwrap_err();
+
+        assert!(is_kind_of(err.value(), rb_sys::rb_eNameError));
+    }
+}
+
+#[ruby_test]
+fn test_obj_dup() {
+    unsafe {
+        let original =
+            eval!("o = Struct.new(:a).new(1); o.instance_variable_set(:@b, 2); o.freeze");
+        let copy = obj_dup(original);
+
+        assert_ne!(copy, original);
+        assert_eq!(rb_obj_frozen_p(copy), rb_sys::Qfalse as VALUE);
+        assert_eq!(instance_variables(copy), ["@b"]);
+        assert_eq!(try_call(copy, "a", &[]).unwrap(), Some(rb_int2inum(1)));
+
+        let nil = Qnil as VALUE;
+        assert_eq!(obj_dup(nil), nil);
+    }
+}
+
+#[ruby_test]
+fn test_deep_eql_nested_structures() {
+    unsafe {
+        let a = eval!("[1, 'two', { three: [3.0, { 'four' => nil }] }, :five]");
+        let b = eval!("[1, 'two', { three: [3.0, { 'four' => nil }] }.freeze, :five]");
+
+        assert_ne!(a, b);
+        assert!(deep_eql(a, b));
+        assert!(deep_eql(eval!("{ a: 1, b: 2 }"), eval!("{ b: 2, a: 1 }")));
+        assert!(deep_eql(Qnil as VALUE, Qnil as VALUE));
+    }
+}
+
+#[ruby_test]
+fn test_deep_eql_differences() {
+    unsafe {
+        assert!(!deep_eql(eval!("[1, [2]]"), eval!("[1, [2.0]]")));
+        assert!(!deep_eql(eval!("[1, [2]]"), eval!("[1, [2, 3]]")));
+        assert!(!deep_eql(eval!("{ a: 1 }"), eval!("{ a: 1, b: 2 }")));
+        assert!(!deep_eql(eval!("{ a: 1 }"), eval!("{ 'a' => 1 }")));
+        assert!(!deep_eql(eval!("[1]"), eval!("{ 1 => nil }")));
+        assert!(!deep_eql(eval!("['a']"), eval!("['b']")));
+    }
+}
+
+#[ruby_test]
+fn test_deep_eql_recursive_structures() {
+    unsafe {
+        let a = eval!("a = [1]; a << a; a");
+        let b = eval!("b = [1]; b << b; b");
+        let c = eval!("c = [2]; c << c; c");
+
+        assert!(deep_eql(a, b));
+        assert!(!deep_eql(a, c));
+    }
+}
+
+#[ruby_test]
+fn test_obj_alloc() {
+    unsafe {
+        let klass = eval!("Class.new { def initialize; @a = 1; end }");
+        let instance = obj_alloc(klass);
+
+        assert!(is_instance_of(instance, klass));
+        assert!(instance_variables(instance).is_empty());
+    }
+}
+
+#[ruby_test]
+fn test_obj_alloc_without_allocator() {
+    let result = unsafe { protect(|| obj_alloc(rb_cInteger)) };
+
+    assert!(result.is_err());
+}
+
+unsafe extern "C" fn free_counter(data: *mut std::ffi::c_void) {
+    drop(Box::from_raw(data as *mut u64));
+}
+
+#[ruby_test(gc_stress)]
+fn test_typed_data_wrap() {
+    unsafe {
+        let data_type: &'static mut rb_data_type_t = Box::leak(Box::new(std::mem::zeroed()));
+        data_type.wrap_struct_name = "RbSysTypedDataWrapTest\0".as_ptr() as _;
+        data_type.function.dfree = Some(free_counter);
+
+        let klass = eval!("Class.new");
+        let counter = Box::into_raw(Box::new(42_u64));
+        let obj = typed_data_wrap(klass, counter as _, data_type);
+
+        assert!(is_instance_of(obj, klass));
+        assert_eq!(rb_check_typeddata(obj, data_type) as *mut u64, counter);
+        assert_eq!(*counter, 42);
+    }
+}
+
+#[ruby_test]
+fn test_taint_helpers_return_the_value() {
+    unsafe {
+        let string = rstring!("hello");
+
+        assert_eq!(taint(string), string);
+        assert_eq!(is_tainted(string), cfg!(not(ruby_gte_2_7)));
+        assert_eq!(untaint(string), string);
+        assert!(!is_tainted(string));
+    }
+}
+
+unsafe extern "C" fn delegating_method_missing(
+    argc: std::os::raw::c_int,
+    argv: *const VALUE,
+    rb_self: VALUE,
+) -> VALUE {
+    let args = std::slice::from_raw_parts(argv, argc as usize);
+    let inner = rb_sys::rb_ivar_get(rb_self, rb_sys::rb_intern("@inner\0".as_ptr() as _));
+    let method = rb_sys::rb_sym2id(args[0]);
+
+    if rb_sys::rb_respond_to(inner, method) != 0 {
+        rb_sys::rb_funcallv(inner, method, argc - 1, argv.add(1))
+    } else {
+        call_super(args)
+    }
+}
+
+#[ruby_test]
+fn test_method_missing_delegates_with_call_super_fallback() {
+    unsafe {
+        let method_missing: unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const VALUE,
+            VALUE,
+        ) -> VALUE = delegating_method_missing;
+
+        rb_sys::class::ClassBuilder::define("RbSysDelegator", rb_cObject)
+            .method("method_missing", method_missing);
+
+        let mut delegated =
+            eval!("o = RbSysDelegator.new; o.instance_variable_set(:@inner, 'hello'); o.upcase");
+        assert_eq!(rstring_to_string!(delegated), "HELLO");
+
+        let err = protect(|| eval!("RbSysDelegator.new.nope")).unwrap_err();
+        assert_eq!(err.class(), rb_sys::rb_eNoMethodError);
+    }
+}
+
+#[ruby_test]
+fn test_raise_no_method_error() {
+    unsafe {
+        let obj = eval!("Object.new");
+        let err = protect(|| {
+            raise_no_method_error(obj, "nope");
+        })
+        .unwrap_err();
+
+        assert_eq!(err.class(), rb_sys::rb_eNoMethodError);
+        assert_eq!(try_call(err.value(), "receiver", &[]).unwrap(), Some(obj));
+        assert_eq!(
+            try_call(err.value(), "name", &[]).unwrap(),
+            Some(eval!(":nope"))
+        );
+    }
+}
@@ -0,0 +1,54 @@
+use rb_sys::exception::protect;
+use rb_sys::proc::proc_new;
+use rb_sys::symbol::intern;
+use rb_sys::{
+    rb_ary_entry, rb_eRuntimeError, rb_funcall_with_block, rb_int2inum, rb_num2long, rb_proc_call,
+    Qnil, RARRAY_LEN, VALUE,
+};
+use rb_sys_test_helpers::{eval, ruby_test};
+use std::cell::Cell;
+use std::ptr::null;
+use std::rc::Rc;
+
+#[ruby_test]
+fn test_proc_new_passed_as_a_block() {
+    unsafe {
+        let doubled = proc_new(|args: &[VALUE]| rb_int2inum((rb_num2long(args[0]) * 2) as _));
+        let array = eval!("[1, 2, 3]");
+        let result = rb_funcall_with_block(array, intern("map"), 0, null(), doubled);
+
+        assert_eq!(RARRAY_LEN(result), 3);
+        assert_eq!(rb_num2long(rb_ary_entry(result, 0)), 2);
+        assert_eq!(rb_num2long(rb_ary_entry(result, 1)), 4);
+        assert_eq!(rb_num2long(rb_ary_entry(result, 2)), 6);
+    }
+}
+
+#[ruby_test]
+fn test_proc_new_keeps_closure_state() {
+    unsafe {
+        let calls = Rc::new(Cell::new(0));
+        let counter = calls.clone();
+        let counting = proc_new(move |args: &[VALUE]| {
+            counter.set(counter.get() + args.len());
+            Qnil as VALUE
+        });
+
+        rb_proc_call(counting, eval!("[1, 2]"));
+        rb_proc_call(counting, eval!("[]"));
+        rb_proc_call(counting, eval!("[3]"));
+
+        assert_eq!(calls.get(), 3);
+    }
+}
+
+#[ruby_test]
+fn test_proc_new_raises_panics() {
+    unsafe {
+        let panicking = proc_new(|_: &[VALUE]| panic!("oh no"));
+        let err = protect(|| rb_proc_call(panicking, eval!("[]"))).unwrap_err();
+
+        assert_eq!(err.class(), rb_eRuntimeError);
+        assert_eq!(err.message().as_deref(), Some("panic in Rust proc: oh no"));
+    }
+}
@@ -0,0 +1,44 @@
+use rb_sys::ractor::{make_shareable, ractor_shareable};
+use rb_sys::{rb_ary_entry, rb_int2inum, rb_obj_frozen_p, Qnil, Qtrue, VALUE};
+use rb_sys_test_helpers::{eval, rstring, ruby_test};
+
+#[ruby_test]
+fn test_immediates_are_shareable() {
+    unsafe {
+        assert!(ractor_shareable(Qnil as VALUE));
+        assert!(ractor_shareable(rb_int2inum(42)));
+        assert!(ractor_shareable(eval!(":sym")));
+    }
+}
+
+#[ruby_test]
+fn test_deeply_frozen_object_is_shareable() {
+    unsafe {
+        let value = eval!("['a'.freeze, { b: 1.5 }.freeze].freeze");
+
+        assert!(ractor_shareable(value));
+    }
+}
+
+#[ruby_test]
+fn test_mutable_object_is_not_shareable() {
+    unsafe {
+        assert!(!ractor_shareable(rstring!("mutable")));
+        assert!(!ractor_shareable(eval!("[1, 2]")));
+        // Freezing is shallow, so the inner string is still mutable.
+        assert!(!ractor_shareable(eval!("[+'a'].freeze")));
+    }
+}
+
+#[ruby_test]
+fn test_make_shareable() {
+    unsafe {
+        let value = eval!("[+'a', { b: [+'c'] }]");
+        let shareable = make_shareable(value);
+
+        assert_eq!(shareable, value);
+        assert!(ractor_shareable(value));
+        assert_eq!(rb_obj_frozen_p(value), Qtrue as VALUE);
+        assert_eq!(rb_obj_frozen_p(rb_ary_entry(value, 0)), Qtrue as VALUE);
+    }
+}
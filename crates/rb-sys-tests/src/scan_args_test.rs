@@ -0,0 +1,175 @@
+use rb_sys::class::define_global_function;
+use rb_sys::exception::{protect, RubyException};
+use rb_sys::scan_args::{KeywordFlag, ScanArgs, ScannedArgs};
+use rb_sys::{
+    rb_ary_entry, rb_eArgError, rb_eval_string, rb_hash_aref, rb_num2long, Qnil, RARRAY_LEN, VALUE,
+};
+use rb_sys_test_helpers::{rsymbol, ruby_test};
+use std::cell::{Cell, RefCell};
+use std::ffi::CString;
+use std::os::raw::c_int;
+
+thread_local! {
+    static SPEC: Cell<ScanArgs> = const { Cell::new(ScanArgs::new()) };
+    static SCANNED: RefCell<Option<ScannedArgs>> = const { RefCell::new(None) };
+}
+
+unsafe extern "C" fn rb_sys_scan(argc: c_int, argv: *const VALUE, _rb_self: VALUE) -> VALUE {
+    let scanned = SPEC.with(|spec| spec.get()).scan(argc, argv);
+    SCANNED.with(|s| *s.borrow_mut() = Some(scanned));
+
+    Qnil as VALUE
+}
+
+/// Evaluates `args` as the arguments of a call to a method which scans them
+/// according to `spec`.
+fn scan(spec: ScanArgs, args: &str) -> Result<ScannedArgs, RubyException> {
+    unsafe {
+        let f: unsafe extern "C" fn(c_int, *const VALUE, VALUE) -> VALUE = rb_sys_scan;
+        define_global_function("rb_sys_scan", f);
+    }
+
+    SPEC.with(|s| s.set(spec));
+    SCANNED.with(|s| s.borrow_mut().take());
+
+    let code = CString::new(format!("rb_sys_scan{}", args)).unwrap();
+    unsafe { protect(|| rb_eval_string(code.as_ptr())) }?;
+
+    Ok(SCANNED
+        .with(|s| s.borrow_mut().take())
+        .expect("method was not called"))
+}
+
+fn int(value: VALUE) -> i64 {
+    unsafe { rb_num2long(value) as _ }
+}
+
+#[ruby_test]
+fn test_scan_required() {
+    let args = scan(ScanArgs::new().required(2), "(1, 2)").unwrap();
+
+    assert_eq!(
+        args.required.iter().map(|v| int(*v)).collect::<Vec<_>>(),
+        [1, 2]
+    );
+    assert!(args.optional.is_empty());
+    assert_eq!(args.splat, None);
+    assert_eq!(args.keywords, None);
+    assert_eq!(args.block, None);
+}
+
+#[ruby_test]
+fn test_scan_no_args() {
+    let args = scan(ScanArgs::new(), "()").unwrap();
+
+    assert!(args.required.is_empty());
+
+    let err = scan(ScanArgs::new(), "(1)").unwrap_err();
+    assert_eq!(unsafe { err.class() }, unsafe { rb_eArgError });
+}
+
+#[ruby_test]
+fn test_scan_wrong_number_of_arguments() {
+    for call in ["(1)", "(1, 2, 3)"] {
+        let err = scan(ScanArgs::new().required(2), call).unwrap_err();
+
+        assert_eq!(unsafe { err.class() }, unsafe { rb_eArgError });
+    }
+}
+
+#[ruby_test]
+fn test_scan_optional() {
+    let spec = ScanArgs::new().required(1).optional(2);
+
+    let args = scan(spec, "(1)").unwrap();
+    assert_eq!(args.optional, [None, None]);
+
+    let args = scan(spec, "(1, 2)").unwrap();
+    assert_eq!(args.optional[0].map(int), Some(2));
+    assert_eq!(args.optional[1], None);
+
+    let args = scan(spec, "(1, nil, 3)").unwrap();
+    assert_eq!(args.optional[0], Some(Qnil as VALUE));
+    assert_eq!(args.optional[1].map(int), Some(3));
+}
+
+#[ruby_test]
+fn test_scan_splat() {
+    let spec = ScanArgs::new().required(1).optional(1).splat();
+
+    let args = scan(spec, "(1)").unwrap();
+    assert_eq!(unsafe { RARRAY_LEN(args.splat.unwrap()) }, 0);
+
+    let args = scan(spec, "(1, 2, 3, 4)").unwrap();
+    let splat = args.splat.unwrap();
+    assert_eq!(args.optional[0].map(int), Some(2));
+    assert_eq!(unsafe { RARRAY_LEN(splat) }, 2);
+    assert_eq!(int(unsafe { rb_ary_entry(splat, 0) }), 3);
+    assert_eq!(int(unsafe { rb_ary_entry(splat, 1) }), 4);
+}
+
+#[ruby_test]
+fn test_scan_keywords() {
+    let spec = ScanArgs::new().required(1).optional(1).keywords();
+
+    let args = scan(spec, "(1)").unwrap();
+    assert_eq!(args.keywords, None);
+
+    let args = scan(spec, "(1, a: 2)").unwrap();
+    let keywords = args.keywords.unwrap();
+    assert_eq!(args.optional, [None]);
+    assert_eq!(int(unsafe { rb_hash_aref(keywords, rsymbol!("a")) }), 2);
+}
+
+#[ruby_test]
+fn test_scan_positional_hash_with_keywords() {
+    let spec = ScanArgs::new().optional(1).keywords();
+
+    // Since Ruby 3.0, a positional `Hash` is not the keywords.
+    #[cfg(ruby_gte_3_0)]
+    {
+        let args = scan(spec, "({ a: 2 })").unwrap();
+        assert!(args.optional[0].is_some());
+        assert_eq!(args.keywords, None);
+    }
+
+    let spec = spec.kw_flag(KeywordFlag::LastHashKeywords);
+    let args = scan(spec, "({ a: 2 })").unwrap();
+    let keywords = args.keywords.unwrap();
+    assert_eq!(args.optional, [None]);
+    assert_eq!(int(unsafe { rb_hash_aref(keywords, rsymbol!("a")) }), 2);
+}
+
+#[ruby_test]
+fn test_scan_block() {
+    let spec = ScanArgs::new().optional(1).block();
+
+    let args = scan(spec, "(1)").unwrap();
+    assert_eq!(args.block, None);
+
+    let args = scan(spec, "(1) { |x| x }").unwrap();
+    assert!(args.block.is_some());
+}
+
+#[ruby_test]
+fn test_scan_everything() {
+    let spec = ScanArgs::new()
+        .required(1)
+        .optional(1)
+        .splat()
+        .keywords()
+        .block();
+    let args = scan(spec, "(1, 2, 3, a: 4) { }").unwrap();
+
+    assert_eq!(int(args.required[0]), 1);
+    assert_eq!(args.optional[0].map(int), Some(2));
+    assert_eq!(unsafe { RARRAY_LEN(args.splat.unwrap()) }, 1);
+    assert!(args.keywords.is_some());
+    assert!(args.block.is_some());
+}
+
+#[test]
+#[should_panic(expected = "at most 9 required arguments")]
+fn test_too_many_required_arguments() {
+    let _ = ScanArgs::new().required(10);
+}
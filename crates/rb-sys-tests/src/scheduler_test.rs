@@ -0,0 +1,88 @@
+use rb_sys::class::define_global_function;
+use rb_sys::scheduler::{
+    current_scheduler, scheduler_block, scheduler_io_wait, scheduler_sleep, scheduler_unblock,
+};
+use rb_sys::{rb_fiber_current, Qnil, Qtrue, VALUE};
+use rb_sys_test_helpers::{eval, rstring_to_string, ruby_test};
+use std::time::Duration;
+
+unsafe extern "C" fn call_scheduler_hooks(_rb_self: VALUE) -> VALUE {
+    let scheduler = current_scheduler().expect("no scheduler");
+    let stdout = eval!("$stdout");
+
+    scheduler_sleep(scheduler, Some(Duration::from_millis(250)));
+    // 1 is `RUBY_IO_READABLE`.
+    scheduler_io_wait(scheduler, stdout, 1, None);
+    scheduler_block(scheduler, stdout, Some(Duration::from_secs(1)));
+    scheduler_unblock(scheduler, stdout, rb_fiber_current());
+
+    Qtrue as VALUE
+}
+
+#[ruby_test]
+fn test_current_scheduler_without_scheduler() {
+    unsafe {
+        assert_eq!(current_scheduler(), None);
+    }
+}
+
+#[ruby_test]
+fn test_scheduler_hooks() {
+    unsafe {
+        let hooks: unsafe extern "C" fn(VALUE) -> VALUE = call_scheduler_hooks;
+        define_global_function("rb_sys_call_scheduler_hooks", hooks);
+
+        let mut calls = eval!(
+            r#"
+            class RbSysTestScheduler
+              attr_reader :calls
+
+              def initialize
+                @calls = []
+              end
+
+              def fiber(&block)
+                Fiber.new(blocking: false, &block).tap(&:resume)
+              end
+
+              def kernel_sleep(duration = nil)
+                @calls << [:kernel_sleep, duration]
+              end
+
+              def io_wait(io, events, timeout)
+                @calls << [:io_wait, events, timeout]
+                events
+              end
+
+              def block(blocker, timeout = nil)
+                @calls << [:block, timeout]
+              end
+
+              def unblock(blocker, fiber)
+                @calls << [:unblock, fiber.is_a?(Fiber)]
+              end
+
+              def close
+              end
+            end
+
+            Thread.new do
+              scheduler = RbSysTestScheduler.new
+              Fiber.set_scheduler(scheduler)
+              Fiber.schedule { rb_sys_call_scheduler_hooks }
+              Fiber.set_scheduler(nil)
+              scheduler.calls.inspect
+            end.value
+            "#
+        );
+
+        assert_eq!(
+            rstring_to_string!(calls),
+            "[[:kernel_sleep, 0.25], [:io_wait, 1, nil], [:block, 1.0], [:unblock, true]]"
+        );
+        assert_eq!(
+            eval!("defined?(Fiber.current_scheduler) && Fiber.current_scheduler"),
+            Qnil as VALUE
+        );
+    }
+}
@@ -31,3 +31,34 @@ fn test_flonum_p() {
 
     assert!(!FLONUM_P(Qnil));
 }
+
+#[ruby_test]
+fn test_const_predicates_match_macros() {
+    const NIL: bool = nil_p(Qnil as VALUE);
+    const FALSE_IS_SPECIAL: bool = special_const_p(Qfalse as VALUE);
+    assert!(NIL);
+    assert!(FALSE_IS_SPECIAL);
+
+    let values = unsafe {
+        [
+            Qnil as VALUE,
+            Qtrue as VALUE,
+            Qfalse as VALUE,
+            rb_num2fix(1),
+            rb_int2big(9999999),
+            rb_float_new(0.0),
+            rb_id2sym(rb_intern_str(rstring!("testconstpredicates"))),
+            rstring!("not special"),
+        ]
+    };
+
+    for value in values {
+        assert_eq!(nil_p(value), NIL_P(value));
+        assert_eq!(rb_test(value), TEST(value));
+        assert_eq!(fixnum_p(value), FIXNUM_P(value));
+        assert_eq!(static_sym_p(value), STATIC_SYM_P(value));
+        assert_eq!(flonum_p(value), FLONUM_P(value));
+        assert_eq!(immediate_p(value), IMMEDIATE_P(value));
+        assert_eq!(special_const_p(value), SPECIAL_CONST_P(value));
+    }
+}
@@ -0,0 +1,381 @@
+use rb_sys::exception::protect;
+use rb_sys::object::is_kind_of;
+use rb_sys::string::{
+    coderange, intern_key, is_ascii_only, str_append_bytes, str_cat_ascii, str_downcase, str_dup,
+    str_encode, str_gsub, str_new_frozen, str_reserve, str_set_bytes, str_split, str_sub,
+    str_tmp_frozen, str_upcase, str_valid_encoding, CodeRange, Encoding,
+};
+use rb_sys::{
+    rb_ary_entry, rb_eEncodingError, rb_eFrozenError, rb_enc_get_index, rb_hash_aref, rb_hash_aset,
+    rb_hash_new, rb_hash_size_num, rb_int2inum, rb_num2long, rb_obj_freeze, rb_obj_frozen_p,
+    rb_reg_nth_match, rb_str_capacity, rb_str_new, rb_str_new_frozen, rb_str_to_inum,
+    rb_utf8_encindex, rb_utf8_str_new, RARRAY_LEN, RSTRING_LEN, VALUE,
+};
+use rb_sys_test_helpers::{eval, rstring, rstring_to_string, ruby_test};
+
+#[ruby_test]
+fn test_str_reserve_grows_capacity() {
+    unsafe {
+        let string = rb_str_new(std::ptr::null(), 0);
+        str_reserve(string, 4096);
+
+        assert!(rb_str_capacity(string) >= 4096);
+        assert_eq!(RSTRING_LEN(string), 0);
+    }
+}
+
+#[ruby_test(gc_stress)]
+fn test_str_append_bytes() {
+    unsafe {
+        let mut string = rb_str_new(std::ptr::null(), 0);
+        str_reserve(string, 30);
+
+        for _ in 0..10 {
+            str_append_bytes(string, b"abc");
+        }
+
+        assert_eq!(RSTRING_LEN(string), 30);
+        assert_eq!(rstring_to_string!(string), "abc".repeat(10));
+    }
+}
+
+#[ruby_test]
+fn test_str_set_bytes_replaces_contents() {
+    unsafe {
+        let mut string = rstring!("hello world");
+
+        str_set_bytes(string, b"bye");
+        assert_eq!(RSTRING_LEN(string), 3);
+        assert_eq!(rstring_to_string!(string), "bye");
+
+        str_set_bytes(string, &[b'x'; 100]);
+        assert_eq!(RSTRING_LEN(string), 100);
+        assert_eq!(rstring_to_string!(string), "x".repeat(100));
+    }
+}
+
+#[ruby_test]
+fn test_str_set_bytes_does_not_modify_shared_strings() {
+    unsafe {
+        let mut original = rstring!("a string which is long enough to not be embedded");
+        let mut copy = rb_str_new_frozen(original);
+
+        str_set_bytes(original, b"changed");
+
+        assert_eq!(rstring_to_string!(original), "changed");
+        assert_eq!(
+            rstring_to_string!(copy),
+            "a string which is long enough to not be embedded"
+        );
+    }
+}
+
+#[ruby_test]
+fn test_str_set_bytes_raises_when_frozen() {
+    unsafe {
+        let string = rb_obj_freeze(rstring!("frozen"));
+        let err = protect(|| str_set_bytes(string, b"nope")).unwrap_err();
+
+        assert_eq!(err.class(), rb_eFrozenError);
+    }
+}
+
+#[ruby_test(gc_stress, frozen_strings)]
+fn test_str_append_bytes_to_string_literal() {
+    unsafe {
+        let literal = eval!("'literal'");
+        let err = protect(|| str_append_bytes(literal, b"!")).unwrap_err();
+
+        assert_eq!(err.class(), rb_eFrozenError);
+    }
+}
+
+#[ruby_test]
+fn test_str_valid_encoding() {
+    unsafe {
+        let ascii = rb_utf8_str_new("hello".as_ptr() as _, 5);
+        let multibyte = rb_utf8_str_new("héllo".as_ptr() as _, "héllo".len() as _);
+        let invalid = rb_utf8_str_new(b"\xff\xfeabc".as_ptr() as _, 5);
+        let binary = rb_str_new(b"\xff\xfe".as_ptr() as _, 2);
+
+        assert!(str_valid_encoding(ascii));
+        assert!(str_valid_encoding(multibyte));
+        assert!(!str_valid_encoding(invalid));
+        assert!(str_valid_encoding(binary));
+    }
+}
+
+#[ruby_test]
+fn test_is_ascii_only() {
+    unsafe {
+        let ascii = rb_utf8_str_new("hello".as_ptr() as _, 5);
+        let multibyte = rb_utf8_str_new("héllo".as_ptr() as _, "héllo".len() as _);
+        let invalid = rb_utf8_str_new(b"\xffabc".as_ptr() as _, 4);
+
+        assert!(is_ascii_only(ascii));
+        assert!(!is_ascii_only(multibyte));
+        assert!(!is_ascii_only(invalid));
+    }
+}
+
+#[ruby_test]
+fn test_coderange() {
+    unsafe {
+        let ascii = rb_utf8_str_new("hello".as_ptr() as _, 5);
+        let multibyte = rb_utf8_str_new("héllo".as_ptr() as _, "héllo".len() as _);
+        let invalid = rb_utf8_str_new(b"\xff\xfeabc".as_ptr() as _, 5);
+        let binary = rb_str_new(b"\xff\xfe".as_ptr() as _, 2);
+
+        assert_eq!(coderange(ascii), CodeRange::SevenBit);
+        assert_eq!(coderange(multibyte), CodeRange::Valid);
+        assert_eq!(coderange(invalid), CodeRange::Broken);
+        assert_eq!(coderange(binary), CodeRange::Valid);
+    }
+}
+
+#[ruby_test]
+fn test_coderange_is_updated_after_modification() {
+    unsafe {
+        let string = rb_utf8_str_new("hello".as_ptr() as _, 5);
+
+        assert_eq!(coderange(string), CodeRange::SevenBit);
+
+        str_append_bytes(string, "é".as_bytes());
+        assert_eq!(coderange(string), CodeRange::Valid);
+
+        str_append_bytes(string, b"\xff");
+        assert_eq!(coderange(string), CodeRange::Broken);
+
+        str_set_bytes(string, b"ok");
+        assert_eq!(coderange(string), CodeRange::SevenBit);
+    }
+}
+
+#[ruby_test]
+fn test_str_encode_round_trip() {
+    unsafe {
+        let text = "こんにちは";
+        let mut utf8 = rb_utf8_str_new(text.as_ptr() as _, text.len() as _);
+        let sjis = str_encode(utf8, Encoding::ShiftJis).unwrap();
+
+        assert_eq!(RSTRING_LEN(sjis), 10);
+        assert_ne!(sjis, utf8);
+
+        let mut back = str_encode(sjis, Encoding::Utf8).unwrap();
+
+        assert_eq!(rstring_to_string!(back), text);
+        assert_eq!(rstring_to_string!(utf8), text);
+    }
+}
+
+#[ruby_test]
+fn test_str_encode_errors() {
+    unsafe {
+        let text = "héllo";
+        let string = rb_utf8_str_new(text.as_ptr() as _, text.len() as _);
+
+        let undefined = str_encode(string, Encoding::UsAscii).unwrap_err();
+        assert!(is_kind_of(undefined.value(), rb_eEncodingError));
+
+        let unknown = str_encode(string, Encoding::Other("NOT-AN-ENCODING")).unwrap_err();
+        assert!(is_kind_of(unknown.value(), rb_eEncodingError));
+    }
+}
+
+#[ruby_test]
+fn test_str_split() {
+    unsafe {
+        let parts = str_split(rstring!("a,b,,c"), ",");
+
+        assert_eq!(RARRAY_LEN(parts), 4);
+        assert_eq!(rstring_to_string!(rb_ary_entry(parts, 0)), "a");
+        assert_eq!(rstring_to_string!(rb_ary_entry(parts, 1)), "b");
+        assert_eq!(rstring_to_string!(rb_ary_entry(parts, 2)), "");
+        assert_eq!(rstring_to_string!(rb_ary_entry(parts, 3)), "c");
+
+        let words = str_split(rstring!("  héllo   wörld "), " ");
+        assert_eq!(RARRAY_LEN(words), 2);
+        assert_eq!(rstring_to_string!(rb_ary_entry(words, 1)), "wörld");
+
+        let unicode = str_split(rstring!("a b→c"), " ");
+        assert_eq!(RARRAY_LEN(unicode), 2);
+        assert_eq!(rstring_to_string!(rb_ary_entry(unicode, 1)), "b→c");
+    }
+}
+
+#[ruby_test]
+fn test_str_split_ignores_monkey_patches() {
+    unsafe {
+        eval!("class String; def split(*); [:patched]; end; end");
+
+        let parts = str_split(rstring!("a,b"), ",");
+
+        eval!("class String; remove_method :split; end");
+
+        assert_eq!(RARRAY_LEN(parts), 2);
+        assert_eq!(rstring_to_string!(rb_ary_entry(parts, 0)), "a");
+    }
+}
+
+#[ruby_test]
+fn test_str_split_non_ascii_separator() {
+    unsafe {
+        let err = protect(|| str_split(rstring!("a→b→c"), "→")).unwrap_err();
+
+        assert_eq!(err.class(), rb_sys::rb_eEncCompatError);
+    }
+}
+
+#[ruby_test]
+fn test_str_upcase_and_downcase() {
+    unsafe {
+        let mut string = rstring!("Héllo Wörld");
+        let mut upper = str_upcase(string);
+        let mut lower = str_downcase(string);
+
+        assert_eq!(rstring_to_string!(upper), "HÉLLO WÖRLD");
+        assert_eq!(rstring_to_string!(lower), "héllo wörld");
+        assert_eq!(rstring_to_string!(string), "Héllo Wörld");
+    }
+}
+
+#[ruby_test]
+fn test_intern_key() {
+    unsafe {
+        let key = intern_key("näme");
+        let mut same = intern_key("näme");
+
+        assert_eq!(key, same);
+        assert_ne!(key, intern_key("other"));
+        assert_eq!(rb_obj_frozen_p(key), rb_sys::Qtrue as VALUE);
+        assert_eq!(rstring_to_string!(same), "näme");
+
+        let hash = rb_hash_new();
+        rb_hash_aset(hash, key, rstring!("first"));
+        rb_hash_aset(hash, same, rstring!("second"));
+
+        let mut value = rb_hash_aref(hash, rstring!("näme"));
+        assert_eq!(rb_hash_size_num(hash), 1);
+        assert_eq!(rstring_to_string!(value), "second");
+    }
+}
+
+#[ruby_test]
+fn test_str_dup_keeps_encoding() {
+    unsafe {
+        let text = "héllo";
+        let frozen = rb_str_new_frozen(rb_utf8_str_new(text.as_ptr() as _, text.len() as _));
+        let mut copy = str_dup(frozen);
+
+        assert_ne!(copy, frozen);
+        assert_eq!(rb_enc_get_index(copy), rb_utf8_encindex());
+        assert_eq!(rb_obj_frozen_p(copy), rb_sys::Qfalse as VALUE);
+        assert_eq!(rstring_to_string!(copy), text);
+
+        let sjis = str_encode(frozen, Encoding::ShiftJis).unwrap();
+        assert_eq!(rb_enc_get_index(str_dup(sjis)), rb_enc_get_index(sjis));
+    }
+}
+
+#[ruby_test]
+fn test_str_gsub_with_computed_replacements() {
+    unsafe {
+        let pattern = eval!("/(\\w)(\\d+)/");
+        let mut matches = vec![];
+        let mut replaced = str_gsub(rstring!("a1 é b22 c333"), pattern, |m| {
+            matches.push(m);
+
+            let digits = rb_reg_nth_match(2, m);
+            let doubled = rb_num2long(rb_str_to_inum(digits, 10, 1)) * 2;
+
+            rb_int2inum(doubled as _)
+        });
+
+        assert_eq!(rstring_to_string!(replaced), "2 é 44 666");
+        assert_eq!(matches.len(), 3);
+
+        let mut first = rb_reg_nth_match(0, matches[0]);
+        assert_eq!(rstring_to_string!(first), "a1");
+    }
+}
+
+#[ruby_test]
+fn test_str_gsub_with_empty_matches() {
+    unsafe {
+        let mut replaced = str_gsub(rstring!("héj"), eval!("//"), |_| rstring!("-"));
+
+        assert_eq!(rstring_to_string!(replaced), "-h-é-j-");
+    }
+}
+
+#[ruby_test]
+fn test_str_sub() {
+    unsafe {
+        let string = rstring!("one two three");
+        let mut replaced = str_sub(string, eval!("/t\\w+/"), |m| {
+            str_upcase(rb_reg_nth_match(0, m))
+        });
+        let mut unchanged = str_sub(string, eval!("/four/"), |_| unreachable!());
+
+        assert_eq!(rstring_to_string!(replaced), "one TWO three");
+        assert_eq!(rstring_to_string!(unchanged), "one two three");
+    }
+}
+
+#[ruby_test]
+fn test_str_cat_ascii() {
+    unsafe {
+        let mut string = rstring!("héllo");
+        assert_eq!(coderange(string), CodeRange::Valid);
+
+        str_cat_ascii(string, ", world");
+        assert_eq!(coderange(string), CodeRange::Valid);
+        assert_eq!(rstring_to_string!(string), "héllo, world");
+
+        let ascii = rstring!("abc");
+        assert_eq!(coderange(ascii), CodeRange::SevenBit);
+        str_cat_ascii(ascii, "def");
+        assert_eq!(coderange(ascii), CodeRange::SevenBit);
+    }
+}
+
+#[ruby_test]
+fn test_str_new_frozen() {
+    unsafe {
+        let string = rstring!("hello");
+        let frozen = str_new_frozen(string);
+
+        assert_ne!(frozen, string);
+        assert_eq!(rb_obj_frozen_p(frozen), rb_sys::Qtrue as VALUE);
+        assert_eq!(rb_obj_frozen_p(string), rb_sys::Qfalse as VALUE);
+        assert_eq!(str_new_frozen(frozen), frozen);
+    }
+}
+
+#[ruby_test(gc_stress)]
+fn test_str_tmp_frozen_survives_mutation() {
+    unsafe {
+        let string = rstring!("hello");
+        let frozen = str_tmp_frozen(string);
+
+        str_append_bytes(string, b" world");
+        str_set_bytes(string, b"goodbye");
+
+        assert_eq!(frozen.as_bytes(), b"hello");
+        assert_eq!(rb_obj_frozen_p(frozen.as_value()), rb_sys::Qtrue as VALUE);
+
+        let mut string = string;
+        assert_eq!(rstring_to_string!(string), "goodbye");
+    }
+}
+
+#[ruby_test(gc_stress)]
+fn test_str_tmp_frozen_on_the_heap() {
+    unsafe {
+        let frozen = Box::new(str_tmp_frozen(rstring!("hello")));
+
+        rb_sys::rb_gc();
+
+        assert_eq!(frozen.as_bytes(), b"hello");
+    }
+}
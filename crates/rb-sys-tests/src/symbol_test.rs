@@ -1,9 +1,12 @@
 use std::slice;
 
+use rb_sys::exception::protect;
+use rb_sys::symbol::{strings_to_symbols, symbols_to_strings};
 use rb_sys::{
-    rb_funcall, rb_id2sym, rb_intern, rb_utf8_str_new, RSTRING_LEN, RSTRING_PTR, STATIC_SYM_P,
+    rb_equal, rb_funcall, rb_id2sym, rb_intern, rb_utf8_str_new, Qtrue, RSTRING_LEN, RSTRING_PTR,
+    STATIC_SYM_P, VALUE,
 };
-use rb_sys_test_helpers::ruby_test;
+use rb_sys_test_helpers::{eval, ruby_test};
 
 #[ruby_test]
 fn test_creates_a_usable_id() {
@@ -43,3 +46,25 @@ fn test_non_usascii() {
     assert!(STATIC_SYM_P(sym1));
     assert!(STATIC_SYM_P(sym2));
 }
+
+#[ruby_test]
+fn test_strings_to_symbols_and_back() {
+    unsafe {
+        let strings = eval!("['a', 'b']");
+        let symbols = strings_to_symbols(strings);
+
+        assert_eq!(rb_equal(symbols, eval!("[:a, :b]")), Qtrue as VALUE);
+        assert_eq!(rb_equal(eval!("['a', 'b']"), strings), Qtrue as VALUE);
+
+        let names = symbols_to_strings(symbols);
+
+        assert_eq!(rb_equal(names, eval!("['a', 'b']")), Qtrue as VALUE);
+    }
+}
+
+#[ruby_test]
+fn test_strings_to_symbols_with_invalid_element() {
+    let result = unsafe { protect(|| strings_to_symbols(eval!("['a', 1]"))) };
+
+    assert!(result.is_err());
+}
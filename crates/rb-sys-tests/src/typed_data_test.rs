@@ -0,0 +1,106 @@
+use rb_sys::class::ClassBuilder;
+use rb_sys::exception::protect;
+use rb_sys::typed_data::{
+    define_alloc, define_initialize_copy, get, new_data_type, wrap, TypedData,
+};
+use rb_sys::{rb_cObject, rb_data_type_t, rb_eTypeError, rb_int2inum, VALUE};
+use rb_sys_test_helpers::{eval, ruby_test};
+
+#[derive(Clone)]
+struct Counter {
+    count: i64,
+}
+
+unsafe impl TypedData for Counter {
+    fn data_type() -> &'static rb_data_type_t {
+        static INIT: std::sync::Once = std::sync::Once::new();
+        static mut DATA_TYPE: Option<&'static rb_data_type_t> = None;
+
+        unsafe {
+            INIT.call_once(|| {
+                DATA_TYPE = Some(Box::leak(Box::new(new_data_type::<Counter>(
+                    "RbSysCounter\0",
+                ))));
+            });
+
+            DATA_TYPE.unwrap()
+        }
+    }
+
+    fn copy(&self) -> Option<Self> {
+        Some(self.clone())
+    }
+}
+
+struct Handle;
+
+unsafe impl TypedData for Handle {
+    fn data_type() -> &'static rb_data_type_t {
+        static INIT: std::sync::Once = std::sync::Once::new();
+        static mut DATA_TYPE: Option<&'static rb_data_type_t> = None;
+
+        unsafe {
+            INIT.call_once(|| {
+                DATA_TYPE = Some(Box::leak(Box::new(new_data_type::<Handle>(
+                    "RbSysHandle\0",
+                ))));
+            });
+
+            DATA_TYPE.unwrap()
+        }
+    }
+}
+
+unsafe extern "C" fn increment(rb_self: VALUE) -> VALUE {
+    let counter = get::<Counter>(rb_self).unwrap();
+    counter.count += 1;
+
+    rb_int2inum(counter.count as _)
+}
+
+#[ruby_test(gc_stress)]
+fn test_dup_copies_wrapped_data() {
+    unsafe {
+        let increment: unsafe extern "C" fn(VALUE) -> VALUE = increment;
+        let klass = ClassBuilder::define("RbSysCounter", rb_cObject)
+            .method("increment", increment)
+            .build();
+        define_alloc::<Counter>(klass);
+        define_initialize_copy::<Counter>(klass);
+
+        let original = wrap(klass, Counter { count: 1 });
+        let copy = rb_sys::rb_obj_dup(original);
+        let clone = rb_sys::rb_obj_clone(original);
+
+        rb_sys::rb_funcall(copy, rb_sys::rb_intern("increment\0".as_ptr() as _), 0);
+
+        assert_ne!(copy, original);
+        assert_eq!(get::<Counter>(original).unwrap().count, 1);
+        assert_eq!(get::<Counter>(copy).unwrap().count, 2);
+        assert_eq!(get::<Counter>(clone).unwrap().count, 1);
+        assert!(get::<Counter>(eval!("RbSysCounter.allocate")).is_none());
+    }
+}
+
+#[ruby_test]
+fn test_dup_without_copy_raises() {
+    unsafe {
+        let klass = ClassBuilder::define("RbSysHandle", rb_cObject).build();
+        define_alloc::<Handle>(klass);
+        define_initialize_copy::<Handle>(klass);
+
+        let handle = wrap(klass, Handle);
+        let err = protect(|| rb_sys::rb_obj_dup(handle)).unwrap_err();
+
+        assert_eq!(err.class(), rb_eTypeError);
+    }
+}
+
+#[ruby_test]
+fn test_get_with_wrong_type_raises() {
+    unsafe {
+        let err = protect(|| get::<Counter>(eval!("Object.new")).is_some()).unwrap_err();
+
+        assert_eq!(err.class(), rb_eTypeError);
+    }
+}
@@ -118,6 +118,11 @@ fn debug_and_exit(rbconfig: &mut RbConfig) {
 fn link_libruby(rbconfig: &mut RbConfig) {
     if is_link_ruby_enabled() {
         rbconfig.link_ruby(is_ruby_static_enabled(rbconfig));
+
+        println!("cargo:rerun-if-env-changed=RB_SYS_RUBY_RPATH");
+        if let Some(rpath) = env::var("RB_SYS_RUBY_RPATH").ok().filter(|v| !v.is_empty()) {
+            rbconfig.rpath(&rpath);
+        }
     }
 }
 
@@ -133,11 +138,23 @@ fn export_cargo_cfg(rbconfig: &mut RbConfig, cap: &mut File) {
         println!("cargo:rustc-cfg=use_global_allocator");
     }
 
+    // Only emitted by the bindings when the header exists, but gates modules.
+    println!(r#"cargo:rustc-check-cfg=cfg(ruby_have_ruby_ractor_h, values("true", "false"))"#);
+    println!(
+        r#"cargo:rustc-check-cfg=cfg(ruby_have_ruby_fiber_scheduler_h, values("true", "false"))"#
+    );
+    println!(r#"cargo:rustc-check-cfg=cfg(ruby_have_ruby_memory_view_h, values("true", "false"))"#);
+
     println!("cargo:rustc-check-cfg=cfg(has_ruby_abi_version)");
     if rbconfig.has_ruby_dln_check_abi() {
         println!("cargo:rustc-cfg=has_ruby_abi_version");
     }
 
+    println!("cargo:rustc-check-cfg=cfg(ruby_debug_build)");
+    if rbconfig.is_debug_build() {
+        println!("cargo:rustc-cfg=ruby_debug_build");
+    }
+
     println!("cargo:rustc-check-cfg=cfg(ruby_engine, values(\"mri\", \"truffleruby\"))");
     match rbconfig.ruby_engine() {
         RubyEngine::Mri => {
@@ -235,6 +252,7 @@ fn export_cargo_cfg(rbconfig: &mut RbConfig, cap: &mut File) {
     cfg_capture_opt!(cap, "cargo:teeny={}", rbconfig.get("TEENY"));
     cfg_capture_opt!(cap, "cargo:patchlevel={}", rbconfig.get("PATCHLEVEL"));
     cfg_capture!(cap, "cargo:engine={}", rbconfig.ruby_engine());
+    cfg_capture!(cap, "cargo:debug_build={}", rbconfig.is_debug_build());
 
     for key in rbconfig.all_keys() {
         cfg_capture!(
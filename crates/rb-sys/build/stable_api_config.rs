@@ -2,7 +2,7 @@ use rb_sys_build::{RbConfig, RubyEngine};
 
 use crate::{
     features::is_env_variable_defined,
-    version::{Version, MIN_SUPPORTED_STABLE_VERSION},
+    version::{Version, LATEST_STABLE_VERSION, MIN_SUPPORTED_STABLE_VERSION},
 };
 use std::{convert::TryFrom, error::Error, path::Path};
 
@@ -92,6 +92,7 @@ impl Strategy {
                     println!("cargo:rustc-cfg=stable_api_has_rust_impl");
                     println!("cargo:rustc-cfg=stable_api_include_rust_impl");
                 } else {
+                    warn_compiled_fallback(current_ruby_version);
                     compile()?;
                     println!("cargo:rustc-cfg=stable_api_enable_compiled_mod");
                     println!("cargo:rustc-cfg=stable_api_export_compiled_as_api");
@@ -143,6 +144,21 @@ fn maybe_warn_old_ruby_version(current_ruby_version: Version) {
     }
 }
 
+/// The Rust implementation of the stable API is only verified against the
+/// layout of known Ruby versions, so let users know when it is not used.
+fn warn_compiled_fallback(current_ruby_version: Version) {
+    let advice = if current_ruby_version > LATEST_STABLE_VERSION {
+        "consider upgrading rb-sys"
+    } else {
+        "this Ruby version is below the verified range"
+    };
+
+    println!(
+        "cargo:warning=The stable API has not been verified for Ruby {} (supported: {} to {}), so rb-sys is falling back to the compiled C implementation (`stable-api-compiled-fallback`). This is slower and is not checked against Ruby's ABI; {}.",
+        current_ruby_version, MIN_SUPPORTED_STABLE_VERSION, LATEST_STABLE_VERSION, advice
+    );
+}
+
 fn compile() -> Result<(), Box<dyn Error>> {
     eprintln!("INFO: Compiling the stable API compiled module");
     let mut build = rb_sys_build::cc::Build::new();
@@ -151,5 +167,12 @@ fn compile() -> Result<(), Box<dyn Error>> {
     eprintln!("cargo:rerun-if-changed={}", path.display());
 
     build.file(path);
+    build.compiler_env("RB_SYS_SHIM_CC");
+
+    println!("cargo:rerun-if-env-changed=RB_SYS_SHIM_CACHE_DIR");
+    if let Some(cache_dir) = std::env::var_os("RB_SYS_SHIM_CACHE_DIR").filter(|d| !d.is_empty()) {
+        build.cache_dir(cache_dir.into());
+    }
+
     build.try_compile("compiled")
 }
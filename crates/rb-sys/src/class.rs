@@ -0,0 +1,486 @@
+//! Helpers for creating Ruby classes and modules at runtime.
+
+use crate::symbol::intern;
+use crate::utils::cstring_or_raise;
+use crate::{
+    rb_cObject, rb_const_set, rb_define_attr, rb_define_class_id, rb_define_class_under,
+    rb_define_const, rb_define_global_function, rb_define_method, rb_define_module_under,
+    rb_define_private_method, rb_define_protected_method, rb_define_singleton_method,
+    rb_module_new, VALUE,
+};
+use std::marker::PhantomData;
+use std::os::raw::c_int;
+
+/// Creates a new anonymous class inheriting from `superclass` (akin to
+/// `Class.new(superclass)`).
+///
+/// Unlike `Class.new`, the `inherited` hook of `superclass` is not called.
+/// The class stays anonymous until it is given a name with [`set_name`].
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `superclass` must be a Ruby `Class` which can be subclassed (i.e. not a
+/// singleton class or `Class` itself).
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::class::class_new;
+///
+/// unsafe {
+///     let klass = class_new(rb_sys::rb_cObject);
+///     let instance = rb_sys::rb_class_new_instance(0, std::ptr::null(), klass);
+/// }
+/// ```
+pub unsafe fn class_new(superclass: VALUE) -> VALUE {
+    // `rb_define_class_id` ignores the ID, but unlike `rb_class_new` it also
+    // sets up the metaclass so singleton methods of `superclass` are inherited.
+    rb_define_class_id(0, superclass)
+}
+
+/// Creates a new anonymous module (akin to `Module.new`).
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+pub unsafe fn module_new() -> VALUE {
+    rb_module_new()
+}
+
+/// Names an anonymous class or module by assigning it to the constant `name`
+/// under `namespace` (or top-level when `namespace` is `None`).
+///
+/// The first constant an anonymous module is assigned to becomes its name, so
+/// `set_name(klass, None, "Foo")` makes `klass.name` return `"Foo"`. Modules
+/// which are already named keep their original name.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `module` must be a Ruby `Class` or `Module`, and `name` must be a valid
+/// constant name (i.e. start with an uppercase letter), which is not checked.
+pub unsafe fn set_name(module: VALUE, namespace: Option<VALUE>, name: &str) {
+    rb_const_set(namespace.unwrap_or(rb_cObject), intern(name), module);
+}
+
+/// Defines (or reopens) the class `name` under the `parent` class or module,
+/// inheriting from `superclass` (akin to `class Parent::Name < Superclass`).
+/// Returns the class, which is named `"Parent::Name"`.
+///
+/// Raises an `ArgumentError` if `name` contains a nul byte, or a `TypeError`
+/// if the constant is already defined as something other than a class with
+/// the same superclass.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `parent` must be a Ruby `Class` or `Module`, and `superclass` a Ruby
+/// `Class` which can be subclassed.
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::class::{define_class_under, define_module_under};
+///
+/// unsafe {
+///     let outer = define_module_under(rb_sys::rb_cObject, "Outer");
+///     let inner = define_class_under(outer, "Inner", rb_sys::rb_cObject);
+/// }
+/// ```
+pub unsafe fn define_class_under(parent: VALUE, name: &str, superclass: VALUE) -> VALUE {
+    let name = cstring_or_raise(name.to_owned());
+
+    rb_define_class_under(parent, name.as_ptr(), superclass)
+}
+
+/// Defines (or reopens) the module `name` under the `parent` class or module
+/// (akin to `module Parent::Name`). Returns the module, which is named
+/// `"Parent::Name"`.
+///
+/// Raises an `ArgumentError` if `name` contains a nul byte, or a `TypeError`
+/// if the constant is already defined as something other than a module.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `parent` must be a Ruby `Class` or `Module`.
+pub unsafe fn define_module_under(parent: VALUE, name: &str) -> VALUE {
+    let name = cstring_or_raise(name.to_owned());
+
+    rb_define_module_under(parent, name.as_ptr())
+}
+
+/// An `extern "C"` function which can be defined as a Ruby method, with its
+/// arity derived from its signature.
+///
+/// This is implemented for functions taking the receiver followed by up to 15
+/// `VALUE` arguments (arity 0 to 15), and for functions taking
+/// `(argc: c_int, argv: *const VALUE, recv: VALUE)` (arity -1).
+///
+/// # Safety
+/// `ARITY` must match the calling convention Ruby will use for the function.
+pub unsafe trait RubyMethod: Copy {
+    /// The arity to pass to `rb_define_method`.
+    const ARITY: c_int;
+
+    /// The function as the untyped (`ANYARGS`) pointer Ruby expects.
+    fn as_raw(self) -> RawMethod;
+}
+
+/// The type of function pointer that `rb_define_method` and friends accept.
+pub type RawMethod = unsafe extern "C" fn() -> VALUE;
+
+macro_rules! impl_ruby_method {
+    ($arity:literal; $($arg:ty),*) => {
+        unsafe impl RubyMethod for extern "C" fn(VALUE $(, $arg)*) -> VALUE {
+            const ARITY: c_int = $arity;
+
+            fn as_raw(self) -> RawMethod {
+                unsafe { std::mem::transmute::<Self, RawMethod>(self) }
+            }
+        }
+
+        unsafe impl RubyMethod for unsafe extern "C" fn(VALUE $(, $arg)*) -> VALUE {
+            const ARITY: c_int = $arity;
+
+            fn as_raw(self) -> RawMethod {
+                unsafe { std::mem::transmute::<Self, RawMethod>(self) }
+            }
+        }
+    };
+}
+
+impl_ruby_method!(0;);
+impl_ruby_method!(1; VALUE);
+impl_ruby_method!(2; VALUE, VALUE);
+impl_ruby_method!(3; VALUE, VALUE, VALUE);
+impl_ruby_method!(4; VALUE, VALUE, VALUE, VALUE);
+impl_ruby_method!(5; VALUE, VALUE, VALUE, VALUE, VALUE);
+impl_ruby_method!(6; VALUE, VALUE, VALUE, VALUE, VALUE, VALUE);
+impl_ruby_method!(7; VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE);
+impl_ruby_method!(8; VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE);
+impl_ruby_method!(9; VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE);
+impl_ruby_method!(10; VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE);
+impl_ruby_method!(11; VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE);
+impl_ruby_method!(12; VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE);
+impl_ruby_method!(13; VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE);
+impl_ruby_method!(14; VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE);
+impl_ruby_method!(15; VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE, VALUE);
+
+unsafe impl RubyMethod for extern "C" fn(c_int, *const VALUE, VALUE) -> VALUE {
+    const ARITY: c_int = -1;
+
+    fn as_raw(self) -> RawMethod {
+        unsafe { std::mem::transmute::<Self, RawMethod>(self) }
+    }
+}
+
+unsafe impl RubyMethod for unsafe extern "C" fn(c_int, *const VALUE, VALUE) -> VALUE {
+    const ARITY: c_int = -1;
+
+    fn as_raw(self) -> RawMethod {
+        unsafe { std::mem::transmute::<Self, RawMethod>(self) }
+    }
+}
+
+/// Defines a public instance method `name` on `klass` (akin to `def`).
+///
+/// Raises an `ArgumentError` if `name` contains a nul byte.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `klass` must be a Ruby `Class` or `Module`.
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::class::define_method;
+/// use rb_sys::VALUE;
+///
+/// extern "C" fn greet(_rb_self: VALUE, name: VALUE) -> VALUE {
+///     name
+/// }
+///
+/// unsafe {
+///     let greet: extern "C" fn(VALUE, VALUE) -> VALUE = greet;
+///     define_method(rb_sys::rb_cObject, "greet", greet);
+/// }
+/// ```
+pub unsafe fn define_method<M: RubyMethod>(klass: VALUE, name: &str, func: M) {
+    let name = cstring_or_raise(name.to_owned());
+
+    rb_define_method(klass, name.as_ptr(), Some(func.as_raw()), M::ARITY);
+}
+
+/// Defines a private instance method `name` on `klass` (akin to `private def`).
+///
+/// Raises an `ArgumentError` if `name` contains a nul byte.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `klass` must be a Ruby `Class` or `Module`.
+pub unsafe fn define_private_method<M: RubyMethod>(klass: VALUE, name: &str, func: M) {
+    let name = cstring_or_raise(name.to_owned());
+
+    rb_define_private_method(klass, name.as_ptr(), Some(func.as_raw()), M::ARITY);
+}
+
+/// Defines a protected instance method `name` on `klass` (akin to
+/// `protected def`).
+///
+/// Raises an `ArgumentError` if `name` contains a nul byte.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `klass` must be a Ruby `Class` or `Module`.
+pub unsafe fn define_protected_method<M: RubyMethod>(klass: VALUE, name: &str, func: M) {
+    let name = cstring_or_raise(name.to_owned());
+
+    rb_define_protected_method(klass, name.as_ptr(), Some(func.as_raw()), M::ARITY);
+}
+
+/// Defines a method `name` on the singleton class of `object`, so only that
+/// object responds to it (akin to `def object.name`). When `object` is a
+/// class or module, this defines a class method.
+///
+/// Raises an `ArgumentError` if `name` contains a nul byte, or a `TypeError`
+/// if `object` cannot have singleton methods (i.e. an `Integer`).
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::class::{class_new, define_singleton_method};
+/// use rb_sys::VALUE;
+///
+/// extern "C" fn create(klass: VALUE) -> VALUE {
+///     unsafe { rb_sys::rb_class_new_instance(0, std::ptr::null(), klass) }
+/// }
+///
+/// unsafe {
+///     let klass = class_new(rb_sys::rb_cObject);
+///     let create: extern "C" fn(VALUE) -> VALUE = create;
+///     define_singleton_method(klass, "create", create);
+/// }
+/// ```
+pub unsafe fn define_singleton_method<M: RubyMethod>(object: VALUE, name: &str, func: M) {
+    let name = cstring_or_raise(name.to_owned());
+
+    rb_define_singleton_method(object, name.as_ptr(), Some(func.as_raw()), M::ARITY);
+}
+
+/// Defines a global function `name`, callable from anywhere without a
+/// receiver (akin to defining a private method on `Kernel` which is also a
+/// singleton method of `Kernel`, like `puts`).
+///
+/// Raises an `ArgumentError` if `name` contains a nul byte.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::class::define_global_function;
+/// use rb_sys::VALUE;
+///
+/// extern "C" fn identity(_rb_self: VALUE, value: VALUE) -> VALUE {
+///     value
+/// }
+///
+/// unsafe {
+///     let identity: extern "C" fn(VALUE, VALUE) -> VALUE = identity;
+///     define_global_function("identity", identity);
+///     rb_sys::rb_eval_string("identity(42)\0".as_ptr() as _);
+/// }
+/// ```
+pub unsafe fn define_global_function<M: RubyMethod>(name: &str, func: M) {
+    let name = cstring_or_raise(name.to_owned());
+
+    rb_define_global_function(name.as_ptr(), Some(func.as_raw()), M::ARITY);
+}
+
+/// Defines a reader method `name` on `klass`, returning the instance variable
+/// `@name` (akin to `attr_reader :name`).
+///
+/// `name` may be given with or without the leading `@`. Raises an
+/// `ArgumentError` if it contains a nul byte, or a `NameError` if it is not a
+/// valid attribute name.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `klass` must be a Ruby `Class` or `Module`.
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::class::{class_new, define_attr_reader};
+///
+/// unsafe {
+///     let klass = class_new(rb_sys::rb_cObject);
+///     define_attr_reader(klass, "name");
+/// }
+/// ```
+pub unsafe fn define_attr_reader(klass: VALUE, name: &str) {
+    define_attr(klass, name, true, false);
+}
+
+/// Defines a writer method `name=` on `klass`, setting the instance variable
+/// `@name` (akin to `attr_writer :name`).
+///
+/// `name` may be given with or without the leading `@`. Raises an
+/// `ArgumentError` if it contains a nul byte, or a `NameError` if it is not a
+/// valid attribute name.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `klass` must be a Ruby `Class` or `Module`.
+pub unsafe fn define_attr_writer(klass: VALUE, name: &str) {
+    define_attr(klass, name, false, true);
+}
+
+/// Defines both a reader `name` and a writer `name=` on `klass` for the
+/// instance variable `@name` (akin to `attr_accessor :name`).
+///
+/// `name` may be given with or without the leading `@`. Raises an
+/// `ArgumentError` if it contains a nul byte, or a `NameError` if it is not a
+/// valid attribute name.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `klass` must be a Ruby `Class` or `Module`.
+pub unsafe fn define_attr_accessor(klass: VALUE, name: &str) {
+    define_attr(klass, name, true, true);
+}
+
+unsafe fn define_attr(klass: VALUE, name: &str, read: bool, write: bool) {
+    // `rb_define_attr` expects the bare attribute name, and adds the `@`
+    // itself when naming the instance variable.
+    let name = name.strip_prefix('@').unwrap_or(name);
+    let name = cstring_or_raise(name.to_owned());
+
+    rb_define_attr(klass, name.as_ptr(), read as c_int, write as c_int);
+}
+
+/// Defines the methods, constants and attributes of a class or module with
+/// chained calls, instead of a separate `define_*` call for each.
+///
+/// Each method is defined with the arity of its signature (see
+/// [`RubyMethod`]), and names containing a nul byte raise an
+/// `ArgumentError`, as with the `define_*` functions.
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::class::ClassBuilder;
+/// use rb_sys::VALUE;
+///
+/// extern "C" fn copy(rb_self: VALUE) -> VALUE {
+///     unsafe { rb_sys::rb_obj_dup(rb_self) }
+/// }
+///
+/// #[no_mangle]
+/// unsafe extern "C" fn Init_rust_reverse() {
+///     let copy: extern "C" fn(VALUE) -> VALUE = copy;
+///     let version = rb_sys::rb_utf8_str_new("1.0".as_ptr() as _, 3);
+///
+///     ClassBuilder::define("RustReverse", rb_sys::rb_cObject)
+///         .method("copy", copy)
+///         .constant("VERSION", version)
+///         .attr_accessor("name");
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct ClassBuilder {
+    klass: VALUE,
+    // Defining methods needs the GVL, so the builder must stay on the thread
+    // it was created on.
+    _not_send: PhantomData<*mut ()>,
+}
+
+impl ClassBuilder {
+    /// Starts building on an existing class or module.
+    ///
+    /// # Safety
+    /// The Ruby VM must be initialized and the current thread must hold the
+    /// GVL while the builder is used. `klass` must be a Ruby `Class` or
+    /// `Module`.
+    pub unsafe fn new(klass: VALUE) -> Self {
+        Self {
+            klass,
+            _not_send: PhantomData,
+        }
+    }
+
+    /// Defines (or reopens) the top-level class `name` inheriting from
+    /// `superclass` (see [`define_class_under`]), and starts building on it.
+    ///
+    /// # Safety
+    /// The Ruby VM must be initialized and the current thread must hold the
+    /// GVL while the builder is used. `superclass` must be a Ruby `Class`
+    /// which can be subclassed.
+    pub unsafe fn define(name: &str, superclass: VALUE) -> Self {
+        Self::new(define_class_under(rb_cObject, name, superclass))
+    }
+
+    /// Defines (or reopens) the class `name` under `parent` inheriting from
+    /// `superclass` (see [`define_class_under`]), and starts building on it.
+    ///
+    /// # Safety
+    /// The Ruby VM must be initialized and the current thread must hold the
+    /// GVL while the builder is used. `parent` must be a Ruby `Class` or
+    /// `Module`, and `superclass` a Ruby `Class` which can be subclassed.
+    pub unsafe fn define_under(parent: VALUE, name: &str, superclass: VALUE) -> Self {
+        Self::new(define_class_under(parent, name, superclass))
+    }
+
+    /// Defines a public instance method (see [`define_method`]).
+    pub fn method<M: RubyMethod>(self, name: &str, func: M) -> Self {
+        unsafe { define_method(self.klass, name, func) };
+        self
+    }
+
+    /// Defines a private instance method (see [`define_private_method`]).
+    pub fn private_method<M: RubyMethod>(self, name: &str, func: M) -> Self {
+        unsafe { define_private_method(self.klass, name, func) };
+        self
+    }
+
+    /// Defines a protected instance method (see [`define_protected_method`]).
+    pub fn protected_method<M: RubyMethod>(self, name: &str, func: M) -> Self {
+        unsafe { define_protected_method(self.klass, name, func) };
+        self
+    }
+
+    /// Defines a class method (see [`define_singleton_method`]).
+    pub fn singleton_method<M: RubyMethod>(self, name: &str, func: M) -> Self {
+        unsafe { define_singleton_method(self.klass, name, func) };
+        self
+    }
+
+    /// Sets the constant `name` to `value` (akin to `NAME = value` in the
+    /// class body). Ruby warns if `name` is not a valid constant name.
+    pub fn constant(self, name: &str, value: VALUE) -> Self {
+        unsafe {
+            let name = cstring_or_raise(name.to_owned());
+
+            rb_define_const(self.klass, name.as_ptr(), value);
+        }
+        self
+    }
+
+    /// Defines a reader for `@name` (see [`define_attr_reader`]).
+    pub fn attr_reader(self, name: &str) -> Self {
+        unsafe { define_attr_reader(self.klass, name) };
+        self
+    }
+
+    /// Defines a writer for `@name` (see [`define_attr_writer`]).
+    pub fn attr_writer(self, name: &str) -> Self {
+        unsafe { define_attr_writer(self.klass, name) };
+        self
+    }
+
+    /// Defines a reader and a writer for `@name` (see
+    /// [`define_attr_accessor`]).
+    pub fn attr_accessor(self, name: &str) -> Self {
+        unsafe { define_attr_accessor(self.klass, name) };
+        self
+    }
+
+    /// The class or module being built.
+    pub fn build(self) -> VALUE {
+        self.klass
+    }
+}
@@ -0,0 +1,378 @@
+//! Helpers for capturing Ruby exceptions from Rust.
+//!
+//! Ruby raises exceptions by `longjmp`-ing up the C stack, which skips Rust
+//! destructors and is undefined behavior if it crosses a frame that expects to
+//! unwind. The functions in this module run Rust code under `rb_protect` so
+//! that exceptions are turned into `Result`s, and Rust panics are carried back
+//! across the FFI boundary instead of aborting. The same goes for the
+//! non-local exits of `catch`/`throw`.
+
+use crate::{
+    rb_catch_obj, rb_ensure, rb_errinfo, rb_exc_new, rb_obj_class, rb_protect, rb_set_errinfo,
+    rb_throw_obj, Qnil, VALUE,
+};
+use std::os::raw::c_int;
+use std::panic::{self, AssertUnwindSafe};
+
+/// A Ruby exception which was raised (and rescued) while running Rust code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RubyException {
+    value: VALUE,
+}
+
+impl RubyException {
+    /// Wraps an exception object.
+    pub fn new(value: VALUE) -> Self {
+        Self { value }
+    }
+
+    /// The underlying exception object.
+    pub fn value(&self) -> VALUE {
+        self.value
+    }
+
+    /// The class of the exception (i.e. `RuntimeError`).
+    ///
+    /// # Safety
+    /// The Ruby VM must be initialized, and the exception object must still be
+    /// live (i.e. not garbage collected).
+    pub unsafe fn class(&self) -> VALUE {
+        rb_obj_class(self.value)
+    }
+
+    /// The message of the exception, if it has one.
+    ///
+    /// # Safety
+    /// The Ruby VM must be initialized, and the exception object must still be
+    /// live (i.e. not garbage collected).
+    #[cfg(feature = "stable-api")]
+    pub unsafe fn message(&self) -> Option<String> {
+        let message = crate::rb_check_funcall(
+            self.value,
+            crate::symbol::intern("message"),
+            0,
+            std::ptr::null(),
+        );
+
+        if message == crate::Qundef as VALUE || crate::NIL_P(message) {
+            return None;
+        }
+
+        let message = crate::rb_check_string_type(message);
+
+        if crate::NIL_P(message) {
+            return None;
+        }
+
+        let ptr = crate::RSTRING_PTR(message) as *const u8;
+        let len = crate::RSTRING_LEN(message) as usize;
+        let bytes = std::slice::from_raw_parts(ptr, len);
+
+        Some(String::from_utf8_lossy(bytes).into_owned())
+    }
+
+    /// Re-raises the exception in the Ruby VM.
+    ///
+    /// # Safety
+    /// This function `longjmp`s, so no Rust values with destructors may be
+    /// live in the calling frames.
+    #[allow(unreachable_code)]
+    pub unsafe fn raise(self) -> ! {
+        crate::rb_exc_raise(self.value);
+        unreachable!()
+    }
+}
+
+/// Creates (but does not raise) an exception of `class` with `message` (akin
+/// to `class.new(message)`).
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `class` must be a subclass of `Exception`.
+pub unsafe fn exception_new(class: VALUE, message: &str) -> VALUE {
+    rb_exc_new(class, message.as_ptr() as _, message.len() as _)
+}
+
+/// Raises an exception of `class` with `message` (akin to `raise class,
+/// message`).
+///
+/// Unlike `rb_raise`, the message is not a format string and does not need to
+/// be nul-terminated, so it is used verbatim.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `class` must be a subclass of `Exception`. This function `longjmp`s, so no
+/// Rust values with destructors (i.e. the `String` holding `message`) may be
+/// live in the calling frames.
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::exception::{protect, raise_exception};
+///
+/// let result = unsafe {
+///     protect(|| raise_exception(rb_sys::rb_eArgError, "100% invalid"))
+/// };
+///
+/// assert!(result.is_err());
+/// ```
+pub unsafe fn raise_exception(class: VALUE, message: &str) -> ! {
+    RubyException::new(exception_new(class, message)).raise()
+}
+
+/// Runs `f`, returning a [`RubyException`] if Ruby raised while it ran (akin
+/// to `begin`/`rescue Exception`).
+///
+/// If `f` panics, the panic is caught before it reaches the C stack and is
+/// resumed once `rb_protect` has returned.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// If an exception is raised, `f` is abandoned mid-way and none of its local
+/// values are dropped.
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::exception::protect;
+///
+/// let result = unsafe {
+///     protect(|| rb_sys::rb_eval_string("raise 'oh no'\0".as_ptr() as _))
+/// };
+///
+/// assert!(result.is_err());
+/// ```
+pub unsafe fn protect<F, T>(f: F) -> Result<T, RubyException>
+where
+    F: FnOnce() -> T,
+{
+    struct Closure<F, T> {
+        func: Option<F>,
+        result: Option<std::thread::Result<T>>,
+    }
+
+    unsafe extern "C" fn trampoline<F, T>(data: VALUE) -> VALUE
+    where
+        F: FnOnce() -> T,
+    {
+        let closure = &mut *(data as *mut Closure<F, T>);
+        let func = closure.func.take().expect("closure called more than once");
+        closure.result = Some(panic::catch_unwind(AssertUnwindSafe(func)));
+
+        Qnil as VALUE
+    }
+
+    let mut closure = Closure {
+        func: Some(f),
+        result: None,
+    };
+    let mut state = 0;
+
+    rb_protect(
+        Some(trampoline::<F, T>),
+        &mut closure as *mut Closure<F, T> as VALUE,
+        &mut state,
+    );
+
+    if state != 0 {
+        let err = rb_errinfo();
+        rb_set_errinfo(Qnil as _);
+
+        return Err(RubyException::new(err));
+    }
+
+    match closure.result.take() {
+        Some(Ok(value)) => Ok(value),
+        Some(Err(payload)) => panic::resume_unwind(payload),
+        None => Err(RubyException::new(rb_errinfo())),
+    }
+}
+
+/// Runs `body`, then always runs `cleanup`, even if `body` raised a Ruby
+/// exception (akin to `begin`/`ensure`).
+///
+/// Since a Ruby exception `longjmp`s past Rust destructors, this is the way to
+/// guarantee that resources are released. If `body` raised, the exception
+/// continues to propagate once `cleanup` has run. Panics in either closure
+/// are caught and resumed after `rb_ensure` returns.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// If `body` raises, this function does not return, so the caller must not
+/// rely on destructors of its own locals running (wrap it in [`protect`] to
+/// recover instead).
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::exception::{ensure, protect};
+///
+/// let file = std::fs::File::open("Cargo.toml").unwrap();
+/// let mut file = Some(file);
+///
+/// let result = unsafe {
+///     protect(|| {
+///         ensure(
+///             || rb_sys::rb_eval_string("raise 'oh no'\0".as_ptr() as _),
+///             || drop(file.take()),
+///         )
+///     })
+/// };
+///
+/// assert!(result.is_err());
+/// assert!(file.is_none());
+/// ```
+pub unsafe fn ensure<B, C, T>(body: B, cleanup: C) -> T
+where
+    B: FnOnce() -> T,
+    C: FnOnce(),
+{
+    struct Body<B, T> {
+        func: Option<B>,
+        result: Option<std::thread::Result<T>>,
+    }
+
+    struct Cleanup<C> {
+        func: Option<C>,
+        result: Option<std::thread::Result<()>>,
+    }
+
+    unsafe extern "C" fn body_trampoline<B, T>(data: VALUE) -> VALUE
+    where
+        B: FnOnce() -> T,
+    {
+        let body = &mut *(data as *mut Body<B, T>);
+        let func = body.func.take().expect("body called more than once");
+        body.result = Some(panic::catch_unwind(AssertUnwindSafe(func)));
+
+        Qnil as VALUE
+    }
+
+    unsafe extern "C" fn cleanup_trampoline<C>(data: VALUE) -> VALUE
+    where
+        C: FnOnce(),
+    {
+        let cleanup = &mut *(data as *mut Cleanup<C>);
+        let func = cleanup.func.take().expect("cleanup called more than once");
+        cleanup.result = Some(panic::catch_unwind(AssertUnwindSafe(func)));
+
+        Qnil as VALUE
+    }
+
+    let mut body = Body {
+        func: Some(body),
+        result: None,
+    };
+    let mut cleanup = Cleanup {
+        func: Some(cleanup),
+        result: None,
+    };
+
+    rb_ensure(
+        Some(body_trampoline::<B, T>),
+        &mut body as *mut Body<B, T> as VALUE,
+        Some(cleanup_trampoline::<C>),
+        &mut cleanup as *mut Cleanup<C> as VALUE,
+    );
+
+    let result = body.result.take().expect("body did not run");
+
+    if let Some(Err(payload)) = cleanup.result.take() {
+        panic::resume_unwind(payload);
+    }
+
+    match result {
+        Ok(value) => value,
+        Err(payload) => panic::resume_unwind(payload),
+    }
+}
+
+/// Runs `body` with `tag` (akin to `catch(tag) { |tag| ... }`), returning the
+/// value thrown to `tag` with [`throw`], or the value returned by `body` if
+/// nothing was thrown.
+///
+/// Values thrown to other tags pass through, as do exceptions. If `body`
+/// panics, the panic is caught before it reaches the C stack and is resumed
+/// once `rb_catch_obj` has returned.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// If `body` throws, it is abandoned mid-way and none of its local values are
+/// dropped.
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::exception::{catch_throw, throw};
+///
+/// unsafe {
+///     let tag = rb_sys::rb_obj_alloc(rb_sys::rb_cObject);
+///     let found = catch_throw(tag, |tag| {
+///         for i in 0..10 {
+///             if i == 3 {
+///                 throw(tag, rb_sys::rb_int2inum(i));
+///             }
+///         }
+///
+///         rb_sys::Qnil as _
+///     });
+/// }
+/// ```
+pub unsafe fn catch_throw<F>(tag: VALUE, body: F) -> VALUE
+where
+    F: FnOnce(VALUE) -> VALUE,
+{
+    struct Body<F> {
+        func: Option<F>,
+        panic: Option<Box<dyn std::any::Any + Send>>,
+    }
+
+    unsafe extern "C" fn trampoline<F>(
+        tag: VALUE,
+        data: VALUE,
+        _argc: c_int,
+        _argv: *const VALUE,
+        _blockarg: VALUE,
+    ) -> VALUE
+    where
+        F: FnOnce(VALUE) -> VALUE,
+    {
+        let body = &mut *(data as *mut Body<F>);
+        let func = body.func.take().expect("body called more than once");
+
+        match panic::catch_unwind(AssertUnwindSafe(|| func(tag))) {
+            Ok(value) => value,
+            Err(payload) => {
+                body.panic = Some(payload);
+                Qnil as VALUE
+            }
+        }
+    }
+
+    let mut body = Body {
+        func: Some(body),
+        panic: None,
+    };
+
+    let result = rb_catch_obj(
+        tag,
+        Some(trampoline::<F>),
+        &mut body as *mut Body<F> as VALUE,
+    );
+
+    if let Some(payload) = body.panic.take() {
+        panic::resume_unwind(payload);
+    }
+
+    result
+}
+
+/// Throws `value` to the enclosing [`catch_throw`] (or Ruby `catch`) for
+/// `tag` (akin to `throw tag, value`). Raises an `UncaughtThrowError` if there
+/// is none.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// This function `longjmp`s, so no Rust values with destructors may be live
+/// in the calling frames.
+#[allow(unreachable_code)]
+pub unsafe fn throw(tag: VALUE, value: VALUE) -> ! {
+    rb_throw_obj(tag, value);
+    unreachable!()
+}
@@ -0,0 +1,46 @@
+//! Helpers for reading and writing Ruby global variables.
+
+use crate::utils::cstring_or_raise;
+use crate::{rb_gv_get, rb_gv_set, VALUE};
+
+/// Gets the value of the global variable `name` (i.e. `$stdout`), or `nil` if
+/// it is not defined. The leading `$` is optional.
+///
+/// Raises an `ArgumentError` if `name` contains a nul byte.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::global::gvar_get;
+///
+/// let stdout = unsafe { gvar_get("$stdout") };
+/// ```
+pub unsafe fn gvar_get(name: &str) -> VALUE {
+    let name = gvar_name(name);
+
+    rb_gv_get(name.as_ptr())
+}
+
+/// Sets the global variable `name` (i.e. `$my_global`) to `value`, defining it
+/// if needed, and returns `value`. The leading `$` is optional.
+///
+/// Raises an `ArgumentError` if `name` contains a nul byte, and may raise for
+/// special globals which validate their value (i.e. `$stdout`).
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+pub unsafe fn gvar_set(name: &str, value: VALUE) -> VALUE {
+    let name = gvar_name(name);
+
+    rb_gv_set(name.as_ptr(), value)
+}
+
+unsafe fn gvar_name(name: &str) -> std::ffi::CString {
+    if name.starts_with('$') {
+        cstring_or_raise(name.to_owned())
+    } else {
+        cstring_or_raise(format!("${}", name))
+    }
+}
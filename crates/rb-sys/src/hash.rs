@@ -0,0 +1,185 @@
+//! Helpers for building and reading Ruby hashes.
+
+use crate::{rb_hash_aset, rb_hash_lookup2, st_retval, Qundef, VALUE};
+use std::os::raw::c_int;
+use std::panic::{self, AssertUnwindSafe};
+
+/// Creates a new, empty hash with room for at least `capa` entries (via
+/// `rb_hash_new_capa`), so filling it up to that size does not rehash.
+///
+/// On Ruby < 3.2, which lacks `rb_hash_new_capa`, this is the same as
+/// `rb_hash_new()`.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+pub unsafe fn hash_new_capa(capa: usize) -> VALUE {
+    #[cfg(ruby_gte_3_2)]
+    return crate::rb_hash_new_capa(capa as _);
+
+    #[cfg(not(ruby_gte_3_2))]
+    {
+        let _ = capa;
+        crate::rb_hash_new()
+    }
+}
+
+/// Creates a new hash from `[key, value]` pairs, pre-sized to fit all of
+/// them. Later pairs win over earlier ones with the same key, like
+/// `Hash[pairs]`.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// The values in `pairs` must be live Ruby objects.
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::hash::hash_from_pairs;
+///
+/// unsafe {
+///     let key = rb_sys::rb_utf8_str_new("answer".as_ptr() as _, 6);
+///     let hash = hash_from_pairs(&[[key, rb_sys::rb_int2inum(42)]]);
+/// }
+/// ```
+pub unsafe fn hash_from_pairs(pairs: &[[VALUE; 2]]) -> VALUE {
+    let hash = hash_new_capa(pairs.len());
+
+    for [key, value] in pairs {
+        rb_hash_aset(hash, *key, *value);
+    }
+
+    hash
+}
+
+/// Looks up `key` in `hash`, returning `None` only if the key is absent. A key
+/// mapped to `nil` returns `Some(Qnil)`, unlike `rb_hash_aref` which returns
+/// `nil` in both cases.
+///
+/// Like `Hash#fetch`, the default value or default proc of `hash` is not used.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `hash` must be a Ruby `Hash`, and `key` a live Ruby object.
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::hash::{hash_from_pairs, hash_lookup};
+///
+/// unsafe {
+///     let key = rb_sys::rb_utf8_str_new("answer".as_ptr() as _, 6);
+///     let hash = hash_from_pairs(&[[key, rb_sys::Qnil as _]]);
+///
+///     assert_eq!(hash_lookup(hash, key), Some(rb_sys::Qnil as _));
+/// }
+/// ```
+pub unsafe fn hash_lookup(hash: VALUE, key: VALUE) -> Option<VALUE> {
+    let value = rb_hash_lookup2(hash, key, Qundef as VALUE);
+
+    if value == Qundef as VALUE {
+        None
+    } else {
+        Some(value)
+    }
+}
+
+/// Merges the entries of `src` into `dest`, where the values from `src` win
+/// for keys in both (akin to `dest.merge!(src)`, via `rb_hash_update_by`).
+/// Returns `dest`.
+///
+/// Raises a `FrozenError` if `dest` is frozen.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `dest` and `src` must be Ruby `Hash`es.
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::hash::hash_merge;
+///
+/// unsafe {
+///     let defaults = rb_sys::rb_eval_string("{ verbose: false, retries: 3 }\0".as_ptr() as _);
+///     let options = rb_sys::rb_eval_string("{ verbose: true }\0".as_ptr() as _);
+///
+///     hash_merge(defaults, options);
+/// }
+/// ```
+pub unsafe fn hash_merge(dest: VALUE, src: VALUE) -> VALUE {
+    crate::rb_hash_update_by(dest, src, None)
+}
+
+/// Merges the entries of `src` into `dest`, calling `resolve(key, old, new)`
+/// for keys in both to pick the value to keep (akin to
+/// `dest.merge!(src) { |key, old, new| ... }`). Returns `dest`.
+///
+/// Raises a `FrozenError` if `dest` is frozen.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `dest` and `src` must be Ruby `Hash`es, and `resolve` must not add keys to
+/// `src`. If `resolve` panics, the merge stops and the panic is resumed once
+/// control is back in Rust, leaving the entries merged so far in `dest`.
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::hash::hash_merge_with;
+///
+/// unsafe {
+///     let counts = rb_sys::rb_eval_string("{ a: 1, b: 2 }\0".as_ptr() as _);
+///     let more = rb_sys::rb_eval_string("{ b: 3 }\0".as_ptr() as _);
+///
+///     // { a: 1, b: 5 }
+///     hash_merge_with(counts, more, |_key, old, new| {
+///         rb_sys::rb_int2inum((rb_sys::rb_num2long(old) + rb_sys::rb_num2long(new)) as _)
+///     });
+/// }
+/// ```
+pub unsafe fn hash_merge_with<F>(dest: VALUE, src: VALUE, mut resolve: F) -> VALUE
+where
+    F: FnMut(VALUE, VALUE, VALUE) -> VALUE,
+{
+    struct Merge<'a> {
+        dest: VALUE,
+        resolve: &'a mut dyn FnMut(VALUE, VALUE, VALUE) -> VALUE,
+        panic: Option<Box<dyn std::any::Any + Send>>,
+    }
+
+    unsafe extern "C" fn merge_entry(key: VALUE, value: VALUE, arg: VALUE) -> c_int {
+        let merge = &mut *(arg as *mut Merge);
+        let value = match hash_lookup(merge.dest, key) {
+            Some(old) => {
+                let resolve = &mut merge.resolve;
+
+                match panic::catch_unwind(AssertUnwindSafe(|| resolve(key, old, value))) {
+                    Ok(value) => value,
+                    Err(payload) => {
+                        merge.panic = Some(payload);
+                        return st_retval::ST_STOP as c_int;
+                    }
+                }
+            }
+            None => value,
+        };
+
+        rb_hash_aset(merge.dest, key, value);
+
+        st_retval::ST_CONTINUE as c_int
+    }
+
+    // Raise up front, like `Hash#merge!`, even if `src` is empty.
+    if crate::rb_obj_frozen_p(dest) == crate::Qtrue as VALUE {
+        crate::rb_error_frozen_object(dest);
+    }
+
+    let mut merge = Merge {
+        dest,
+        resolve: &mut resolve,
+        panic: None,
+    };
+
+    crate::rb_hash_foreach(src, Some(merge_entry), &mut merge as *mut Merge as VALUE);
+
+    if let Some(payload) = merge.panic.take() {
+        panic::resume_unwind(payload);
+    }
+
+    dest
+}
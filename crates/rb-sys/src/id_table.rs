@@ -0,0 +1,123 @@
+//! A table of Ruby values keyed by [`ID`], for method dispatch tables and
+//! other lookups by interned name.
+//!
+//! Ruby's own `rb_id_table` is internal to the VM and not exported from
+//! libruby, so [`IdTable`] is built on the public `st_table` API
+//! (`ruby/st.h`) instead, using a numeric table since IDs are integers. The
+//! `rb_st_*` names it uses were added in Ruby 2.7, so this module requires
+//! Ruby 2.7 or later.
+
+use crate::{
+    rb_mark_tbl, rb_st_delete, rb_st_free_table, rb_st_init_numtable_with_size, rb_st_insert,
+    rb_st_lookup, rb_st_memsize, st_data_t, st_index_t, st_table, ID, VALUE,
+};
+
+/// A map from [`ID`]s to Ruby values, backed by a numeric `st_table`.
+///
+/// The values are only kept alive if the table is marked, so an object which
+/// owns an `IdTable` must call [`IdTable::mark`] from its `dmark` function.
+/// The table is freed when it is dropped.
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::id_table::IdTable;
+/// use rb_sys::symbol::intern;
+///
+/// unsafe {
+///     let mut table = IdTable::new();
+///     let value = rb_sys::rb_utf8_str_new("value".as_ptr() as _, 5);
+///
+///     table.insert(intern("key"), value);
+///
+///     assert_eq!(table.get(intern("key")), Some(value));
+/// }
+/// ```
+#[derive(Debug)]
+pub struct IdTable {
+    table: *mut st_table,
+}
+
+impl IdTable {
+    /// Creates an empty table.
+    ///
+    /// # Safety
+    /// The Ruby VM must be initialized, and the values inserted into the table
+    /// must be marked with [`IdTable::mark`] for as long as they are used.
+    pub unsafe fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    /// Creates an empty table with room for at least `capacity` entries.
+    ///
+    /// # Safety
+    /// The Ruby VM must be initialized, and the values inserted into the table
+    /// must be marked with [`IdTable::mark`] for as long as they are used.
+    pub unsafe fn with_capacity(capacity: usize) -> Self {
+        Self {
+            table: rb_st_init_numtable_with_size(capacity as st_index_t),
+        }
+    }
+
+    /// Returns the value for `id`, if any.
+    pub fn get(&self, id: ID) -> Option<VALUE> {
+        let mut value: st_data_t = 0;
+        let found = unsafe { rb_st_lookup(self.table, id as st_data_t, &mut value) };
+
+        (found != 0).then_some(value as VALUE)
+    }
+
+    /// Checks if the table has a value for `id`.
+    pub fn contains(&self, id: ID) -> bool {
+        self.get(id).is_some()
+    }
+
+    /// Sets the value for `id`, returning the previous one, if any.
+    pub fn insert(&mut self, id: ID, value: VALUE) -> Option<VALUE> {
+        let previous = self.get(id);
+
+        unsafe { rb_st_insert(self.table, id as st_data_t, value as st_data_t) };
+
+        previous
+    }
+
+    /// Removes the value for `id`, returning it, if any.
+    pub fn remove(&mut self, id: ID) -> Option<VALUE> {
+        let mut key = id as st_data_t;
+        let mut value: st_data_t = 0;
+        let found = unsafe { rb_st_delete(self.table, &mut key, &mut value) };
+
+        (found != 0).then_some(value as VALUE)
+    }
+
+    /// The number of entries in the table.
+    pub fn len(&self) -> usize {
+        unsafe { (*self.table).num_entries as usize }
+    }
+
+    /// Checks if the table has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The memory used by the table, in bytes (i.e. for the `dsize` function
+    /// of the object which owns it).
+    pub fn memsize(&self) -> usize {
+        unsafe { rb_st_memsize(self.table) as usize }
+    }
+
+    /// Marks every value in the table (via `rb_mark_tbl`), so they are not
+    /// collected. The values are pinned, so GC compaction will not move them.
+    ///
+    /// # Safety
+    /// This must only be called during the mark phase of the GC (i.e. from a
+    /// `dmark` function).
+    pub unsafe fn mark(&self) {
+        rb_mark_tbl(self.table);
+    }
+}
+
+impl Drop for IdTable {
+    fn drop(&mut self) {
+        unsafe { rb_st_free_table(self.table) };
+    }
+}
@@ -2,14 +2,32 @@
 #![doc = include_str!("../readme.md")]
 
 pub mod bindings;
+pub mod class;
+pub mod exception;
+pub mod global;
+pub mod hash;
+#[cfg(ruby_gte_2_7)]
+pub mod id_table;
 #[cfg(feature = "stable-api")]
 pub mod macros;
 pub mod memory;
+#[cfg(ruby_have_ruby_memory_view_h = "true")]
+pub mod memory_view;
+pub mod numeric;
+pub mod object;
+pub mod proc;
+#[cfg(ruby_have_ruby_ractor_h = "true")]
+pub mod ractor;
+pub mod scan_args;
+#[cfg(ruby_have_ruby_fiber_scheduler_h = "true")]
+pub mod scheduler;
 pub mod special_consts;
 #[cfg(feature = "stable-api")]
 pub mod stable_api;
+pub mod string;
 pub mod symbol;
 pub mod tracking_allocator;
+pub mod typed_data;
 pub mod value_type;
 
 mod hidden;
@@ -51,3 +51,120 @@ macro_rules! rb_gc_guard {
         }
     }};
 }
+
+/// Marks every object in `values` (via `rb_gc_mark_locations`), which is
+/// the idiomatic way to mark a contiguous buffer of `VALUE`s from a
+/// `dmark` function.
+///
+/// Note that the objects are marked conservatively, so they are pinned and
+/// will not be moved by GC compaction.
+///
+/// # Safety
+/// Must only be called during the mark phase of the GC (i.e. from a `dmark`
+/// function).
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::{memory::mark_slice, VALUE};
+///
+/// unsafe extern "C" fn dmark(data: *mut std::ffi::c_void) {
+///     let values = &*(data as *const Vec<VALUE>);
+///     mark_slice(values);
+/// }
+/// ```
+pub unsafe fn mark_slice(values: &[crate::VALUE]) {
+    let range = values.as_ptr_range();
+
+    crate::rb_gc_mark_locations(range.start, range.end);
+}
+
+/// Keeps `value` alive for the rest of the life of the process (via
+/// `rb_gc_register_mark_object`), and returns it.
+///
+/// This is meant for values which are created once and cached forever (i.e.
+/// an anonymous class or a frozen string stored in a Rust `static`). The
+/// registration can **not** be undone, so `value` is never freed, even if it
+/// is no longer used: registering a new object per call leaks memory. For a
+/// value which should be kept alive only while a Rust variable points to it,
+/// use `rb_gc_register_address` and `rb_gc_unregister_address` instead.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `value` must be a live Ruby object.
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::memory::register_mark_object;
+///
+/// unsafe {
+///     // Created once (i.e. in `Init_my_ext`), and then cached in a `static`.
+///     let klass = register_mark_object(rb_sys::class::class_new(rb_sys::rb_cObject));
+/// }
+/// ```
+pub unsafe fn register_mark_object(value: crate::VALUE) -> crate::VALUE {
+    crate::rb_gc_register_mark_object(value);
+
+    value
+}
+
+/// Reports `delta` bytes of memory allocated outside of Ruby's heap (or freed,
+/// when negative) to the GC (via `rb_gc_adjust_memory_usage`), so it can take
+/// them into account when deciding to run. Returns the delta which was
+/// reported, which is `0` if the Ruby VM is not running or on engines other
+/// than MRI.
+///
+/// Use [`size_delta`] to compute the delta between two sizes without
+/// overflowing.
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::memory::adjust_memory;
+///
+/// // Allocate 1024 bytes of memory using `mmap` or `malloc`...
+/// adjust_memory(1024);
+///
+/// // ...and then after the memory is freed, adjust the memory usage again.
+/// adjust_memory(-1024);
+/// ```
+#[inline]
+pub fn adjust_memory(delta: isize) -> isize {
+    if delta == 0 {
+        return 0;
+    }
+
+    #[cfg(ruby_engine = "mri")]
+    return unsafe {
+        if crate::utils::is_ruby_vm_started() {
+            crate::rb_gc_adjust_memory_usage(delta as _);
+            delta
+        } else {
+            0
+        }
+    };
+
+    #[cfg(not(ruby_engine = "mri"))]
+    0
+}
+
+/// The signed difference from `old_size` to `new_size` in bytes (i.e. for a
+/// `realloc`), saturating at `isize::MIN` and `isize::MAX` instead of
+/// overflowing.
+///
+/// # Example
+/// ```
+/// use rb_sys::memory::size_delta;
+///
+/// assert_eq!(size_delta(8, 16), 8);
+/// assert_eq!(size_delta(16, 8), -8);
+/// assert_eq!(size_delta(0, usize::MAX), isize::MAX);
+/// ```
+#[inline]
+pub fn size_delta(old_size: usize, new_size: usize) -> isize {
+    if new_size >= old_size {
+        isize::try_from(new_size - old_size).unwrap_or(isize::MAX)
+    } else {
+        isize::try_from(old_size - new_size)
+            .map(|delta| -delta)
+            .unwrap_or(isize::MIN)
+    }
+}
@@ -0,0 +1,203 @@
+//! Helpers for the Memory View protocol (`ruby/memory_view.h`), which lets
+//! extensions share buffers with each other without copying them.
+//!
+//! To export a buffer, register a [`rb_memory_view_entry_t`] for a class
+//! with [`register`], and fill in the view from its `get_func` with
+//! [`export_bytes`] or [`export_bytes_mut`]. To import one, use
+//! [`MemoryView::get`].
+
+use crate::{
+    rb_memory_view_available_p, rb_memory_view_entry_t, rb_memory_view_get,
+    rb_memory_view_init_as_byte_array, rb_memory_view_register, rb_memory_view_release,
+    rb_memory_view_t, ruby_memory_view_flags, VALUE,
+};
+use std::ffi::CStr;
+use std::os::raw::c_int;
+
+/// Registers `entry` as the memory view implementation for instances of
+/// `klass` (and its subclasses). Returns `false` if `klass` already has one.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `klass` must be a Ruby `Class`, and the functions in `entry` must uphold
+/// the protocol (i.e. `release_func` undoes whatever `get_func` did).
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::memory_view::{export_bytes, register};
+/// use rb_sys::{rb_memory_view_entry_t, rb_memory_view_t, VALUE};
+/// use std::os::raw::c_int;
+///
+/// static DATA: [u8; 4] = [1, 2, 3, 4];
+///
+/// unsafe extern "C" fn get(obj: VALUE, view: *mut rb_memory_view_t, _flags: c_int) -> bool {
+///     export_bytes(view, obj, &DATA)
+/// }
+///
+/// unsafe extern "C" fn release(_obj: VALUE, _view: *mut rb_memory_view_t) -> bool {
+///     true
+/// }
+///
+/// unsafe extern "C" fn available(_obj: VALUE) -> bool {
+///     true
+/// }
+///
+/// static ENTRY: rb_memory_view_entry_t = rb_memory_view_entry_t {
+///     get_func: Some(get),
+///     release_func: Some(release),
+///     available_p_func: Some(available),
+/// };
+///
+/// unsafe {
+///     let klass = rb_sys::class::class_new(rb_sys::rb_cObject);
+///     register(klass, &ENTRY);
+/// }
+/// ```
+pub unsafe fn register(klass: VALUE, entry: &'static rb_memory_view_entry_t) -> bool {
+    rb_memory_view_register(klass, entry)
+}
+
+/// Fills in `view` as a read-only, one-dimensional view of `bytes` exported
+/// by `obj`. Call this from the `get_func` of a [`rb_memory_view_entry_t`].
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `view` must be the view passed to `get_func`, and `bytes` must stay valid
+/// (and not be mutated) until the view is released.
+pub unsafe fn export_bytes(view: *mut rb_memory_view_t, obj: VALUE, bytes: &[u8]) -> bool {
+    rb_memory_view_init_as_byte_array(view, obj, bytes.as_ptr() as _, bytes.len() as _, true)
+}
+
+/// Fills in `view` as a writable, one-dimensional view of `bytes` exported by
+/// `obj`. Call this from the `get_func` of a [`rb_memory_view_entry_t`].
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `view` must be the view passed to `get_func`, and `bytes` must stay valid
+/// (and not be accessed by anything else) until the view is released.
+pub unsafe fn export_bytes_mut(view: *mut rb_memory_view_t, obj: VALUE, bytes: &mut [u8]) -> bool {
+    rb_memory_view_init_as_byte_array(view, obj, bytes.as_mut_ptr() as _, bytes.len() as _, false)
+}
+
+/// Checks if `obj` can export a memory view (akin to
+/// `rb_memory_view_available_p`).
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+pub unsafe fn available(obj: VALUE) -> bool {
+    rb_memory_view_available_p(obj)
+}
+
+/// A memory view imported from a Ruby object, which is released when it is
+/// dropped. The object is kept alive until then.
+#[derive(Debug)]
+pub struct MemoryView {
+    view: rb_memory_view_t,
+}
+
+impl MemoryView {
+    /// Gets a memory view of `obj`, which must also be writable if `writable`
+    /// is true. Returns `None` if `obj` does not support memory views, or can
+    /// not provide one with the requested flags.
+    ///
+    /// # Safety
+    /// The Ruby VM must be initialized and the current thread must hold the
+    /// GVL, both here and when the view is dropped.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use rb_sys::memory_view::MemoryView;
+    ///
+    /// unsafe {
+    ///     let obj = rb_sys::rb_eval_string("Fiddle::Pointer.malloc(8)\0".as_ptr() as _);
+    ///
+    ///     if let Some(view) = MemoryView::get(obj, false) {
+    ///         println!("{:?}", view.as_bytes());
+    ///     }
+    /// }
+    /// ```
+    pub unsafe fn get(obj: VALUE, writable: bool) -> Option<Self> {
+        let flags = if writable {
+            ruby_memory_view_flags::RUBY_MEMORY_VIEW_WRITABLE
+        } else {
+            ruby_memory_view_flags::RUBY_MEMORY_VIEW_SIMPLE
+        };
+        let mut view = std::mem::zeroed::<rb_memory_view_t>();
+
+        if rb_memory_view_get(obj, &mut view, flags as c_int) {
+            Some(Self { view })
+        } else {
+            None
+        }
+    }
+
+    /// The object which exported the view.
+    pub fn obj(&self) -> VALUE {
+        self.view.obj
+    }
+
+    /// The exported bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        if self.view.data.is_null() {
+            return &[];
+        }
+
+        unsafe { std::slice::from_raw_parts(self.view.data as *const u8, self.len()) }
+    }
+
+    /// The exported bytes, or `None` if the view is read-only.
+    pub fn as_bytes_mut(&mut self) -> Option<&mut [u8]> {
+        if self.is_readonly() {
+            return None;
+        }
+
+        if self.view.data.is_null() {
+            return Some(&mut []);
+        }
+
+        Some(unsafe { std::slice::from_raw_parts_mut(self.view.data as *mut u8, self.len()) })
+    }
+
+    /// The size of the view in bytes.
+    pub fn len(&self) -> usize {
+        self.view.byte_size as usize
+    }
+
+    /// Checks if the view is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Checks if the view can not be written to.
+    pub fn is_readonly(&self) -> bool {
+        self.view.readonly
+    }
+
+    /// The format of each item, as a `pack` template (i.e. `"l<"`), or `None`
+    /// for unsigned bytes (`"C"`).
+    pub fn format(&self) -> Option<&str> {
+        if self.view.format.is_null() {
+            return None;
+        }
+
+        unsafe { CStr::from_ptr(self.view.format) }.to_str().ok()
+    }
+
+    /// The size of each item in bytes.
+    pub fn item_size(&self) -> usize {
+        self.view.item_size as usize
+    }
+
+    /// The number of dimensions.
+    pub fn ndim(&self) -> usize {
+        self.view.ndim as usize
+    }
+}
+
+impl Drop for MemoryView {
+    fn drop(&mut self) {
+        unsafe {
+            rb_memory_view_release(&mut self.view);
+        }
+    }
+}
@@ -0,0 +1,155 @@
+//! Helpers for converting between Ruby numbers and Rust types.
+
+use crate::exception::exception_new;
+use crate::{
+    rb_absint_size, rb_big2str, rb_eArgError, rb_exc_raise, rb_fix2str, rb_float_new,
+    rb_integer_pack, rb_integer_unpack, rb_num2dbl, rb_str_to_inum, rb_string_value_cstr,
+    rb_to_int, rb_utf8_str_new, INTEGER_PACK_2COMP, INTEGER_PACK_LSBYTE_FIRST,
+    INTEGER_PACK_LSWORD_FIRST, VALUE,
+};
+use std::ffi::CStr;
+use std::os::raw::c_int;
+
+/// Little-endian, two's complement, with one byte per "word".
+const LE_BYTES_FLAGS: c_int =
+    (INTEGER_PACK_LSWORD_FIRST | INTEGER_PACK_LSBYTE_FIRST | INTEGER_PACK_2COMP) as c_int;
+
+/// Converts an `Integer` of any size to its little-endian two's complement
+/// bytes (like `i128::to_le_bytes`, but without the size limit). The result
+/// is as short as possible while preserving the sign, and is never empty.
+///
+/// Raises a `TypeError` if `value` can not be converted to an `Integer`.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::numeric::integer_to_le_bytes;
+///
+/// unsafe {
+///     let value = rb_sys::rb_eval_string("2**64\0".as_ptr() as _);
+///
+///     assert_eq!(integer_to_le_bytes(value), [0, 0, 0, 0, 0, 0, 0, 0, 1]);
+/// }
+/// ```
+pub unsafe fn integer_to_le_bytes(value: VALUE) -> Vec<u8> {
+    let value = rb_to_int(value);
+    // One extra byte so there is always room for the sign bit.
+    let len = rb_absint_size(value, std::ptr::null_mut()) + 1;
+    let mut bytes = vec![0u8; len];
+
+    rb_integer_pack(value, bytes.as_mut_ptr() as _, len, 1, 0, LE_BYTES_FLAGS);
+
+    // Drop redundant sign extension bytes.
+    while let [.., prev, last] = bytes[..] {
+        let is_positive_padding = last == 0x00 && prev & 0x80 == 0;
+        let is_negative_padding = last == 0xff && prev & 0x80 != 0;
+
+        if is_positive_padding || is_negative_padding {
+            bytes.pop();
+        } else {
+            break;
+        }
+    }
+
+    bytes
+}
+
+/// Creates an `Integer` from little-endian two's complement bytes (like
+/// `i128::from_le_bytes`, but of any length). An empty slice is `0`.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+pub unsafe fn integer_from_le_bytes(bytes: &[u8]) -> VALUE {
+    rb_integer_unpack(bytes.as_ptr() as _, bytes.len(), 1, 0, LE_BYTES_FLAGS)
+}
+
+/// Formats an `Integer` of any size as a string in `base` (akin to
+/// `Integer#to_s(base)`), i.e. `"ff"` for `255` in base 16. Negative numbers
+/// are prefixed with `-`.
+///
+/// Raises an `ArgumentError` if `base` is not between 2 and 36, or a
+/// `TypeError` if `value` can not be converted to an `Integer`.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::numeric::integer_to_string;
+///
+/// unsafe {
+///     let value = rb_sys::rb_eval_string("2**64\0".as_ptr() as _);
+///
+///     assert_eq!(integer_to_string(value, 16), "10000000000000000");
+/// }
+/// ```
+pub unsafe fn integer_to_string(value: VALUE, base: u32) -> String {
+    let value = rb_to_int(value);
+    let mut string = if crate::special_consts::fixnum_p(value) {
+        rb_fix2str(value, base as c_int)
+    } else {
+        rb_big2str(value, base as c_int)
+    };
+    let cstr = rb_string_value_cstr(&mut string);
+
+    CStr::from_ptr(cstr).to_string_lossy().into_owned()
+}
+
+/// Parses an `Integer` of any size from `digits` in `base` (akin to
+/// `Integer(digits, base)`). Underscores between digits and a leading sign
+/// are allowed, as is a prefix matching `base` (i.e. `0x` for base 16).
+///
+/// Raises an `ArgumentError` if `digits` is not a valid integer in `base`, or
+/// if `base` is not between 2 and 36. Unlike `Integer(digits, 0)`, a `base`
+/// of 0 is rejected rather than detected from the prefix.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+pub unsafe fn integer_from_string(digits: &str, base: u32) -> VALUE {
+    if !(2..=36).contains(&base) {
+        let message = format!("invalid radix {}", base);
+        let error = exception_new(rb_eArgError, &message);
+
+        // Raising skips destructors, so `message` would leak.
+        drop(message);
+        rb_exc_raise(error)
+    }
+
+    let string = rb_utf8_str_new(digits.as_ptr() as _, digits.len() as _);
+
+    rb_str_to_inum(string, base as c_int, 1)
+}
+
+/// Converts a Ruby `Numeric` to an `f64` (akin to `Float(value)`, but without
+/// parsing strings).
+///
+/// Integers, rationals and other numerics are coerced, so large integers may
+/// lose precision. Raises a `TypeError` for `nil`, strings, and other values
+/// which are not numeric.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::numeric::to_f64;
+///
+/// unsafe {
+///     let value = rb_sys::rb_eval_string("1/4r\0".as_ptr() as _);
+///
+///     assert_eq!(to_f64(value), 0.25);
+/// }
+/// ```
+pub unsafe fn to_f64(value: VALUE) -> f64 {
+    rb_num2dbl(value)
+}
+
+/// Creates a Ruby `Float` from an `f64`.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+pub unsafe fn f64_to_ruby(value: f64) -> VALUE {
+    rb_float_new(value)
+}
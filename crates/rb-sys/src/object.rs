@@ -0,0 +1,574 @@
+//! Helpers for working with arbitrary Ruby objects.
+
+use crate::exception::{protect, RubyException};
+use crate::symbol::intern;
+use crate::{
+    rb_check_funcall, rb_data_type_t, rb_funcallv, rb_id2sym, rb_obj_as_string,
+    rb_obj_is_instance_of, rb_obj_is_kind_of, rb_obj_method, rb_respond_to, Qtrue, Qundef, VALUE,
+};
+use std::os::raw::{c_int, c_void};
+
+/// Checks if `recv` responds to the public method `method` (akin to
+/// `recv.respond_to?(method)`).
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+pub unsafe fn respond_to(recv: VALUE, method: &str) -> bool {
+    rb_respond_to(recv, intern(method)) != 0
+}
+
+/// Checks if `value` is an instance of exactly `class` (akin to
+/// `value.instance_of?(class)`), so instances of subclasses do not match.
+///
+/// Raises a `TypeError` if `class` is not a `Class` or `Module`.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::object::{is_instance_of, is_kind_of};
+///
+/// unsafe {
+///     let error = rb_sys::rb_eval_string("ArgumentError.new\0".as_ptr() as _);
+///
+///     assert!(is_kind_of(error, rb_sys::rb_eStandardError));
+///     assert!(!is_instance_of(error, rb_sys::rb_eStandardError));
+/// }
+/// ```
+pub unsafe fn is_instance_of(value: VALUE, class: VALUE) -> bool {
+    rb_obj_is_instance_of(value, class) == Qtrue as VALUE
+}
+
+/// Checks if `value` is an instance of `class`, one of its subclasses, or
+/// includes it when `class` is a `Module` (akin to `value.is_a?(class)`).
+///
+/// Raises a `TypeError` if `class` is not a `Class` or `Module`.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+pub unsafe fn is_kind_of(value: VALUE, class: VALUE) -> bool {
+    rb_obj_is_kind_of(value, class) == Qtrue as VALUE
+}
+
+/// Calls `method` on `recv` with the given arguments, rescuing any exception
+/// it raises.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// The values in `args` must be live Ruby objects.
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::object::call_method;
+///
+/// unsafe {
+///     let string = rb_sys::rb_utf8_str_new("hello".as_ptr() as _, 5);
+///     let reversed = call_method(string, "reverse", &[]).unwrap();
+/// }
+/// ```
+pub unsafe fn call_method(
+    recv: VALUE,
+    method: &str,
+    args: &[VALUE],
+) -> Result<VALUE, RubyException> {
+    let id = intern(method);
+
+    protect(|| rb_funcallv(recv, id, args.len() as c_int, args.as_ptr()))
+}
+
+/// Calls `method` on `recv` only if it responds to it, returning `Ok(None)`
+/// otherwise. This is the duck-typing counterpart of [`call_method`], and
+/// respects `respond_to_missing?` the same way Ruby's own `rb_check_funcall`
+/// does.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// The values in `args` must be live Ruby objects.
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::object::try_call;
+///
+/// unsafe {
+///     let obj = rb_sys::rb_eval_string("Object.new\0".as_ptr() as _);
+///     let size = try_call(obj, "size", &[]).unwrap();
+///
+///     assert_eq!(size, None);
+/// }
+/// ```
+pub unsafe fn try_call(
+    recv: VALUE,
+    method: &str,
+    args: &[VALUE],
+) -> Result<Option<VALUE>, RubyException> {
+    let id = intern(method);
+    let result = protect(|| rb_check_funcall(recv, id, args.len() as c_int, args.as_ptr()))?;
+
+    if result == Qundef as VALUE {
+        Ok(None)
+    } else {
+        Ok(Some(result))
+    }
+}
+
+/// Converts `value` to a Ruby string by calling its `to_s` method (akin to
+/// `"#{value}"`), rescuing any exception it raises.
+///
+/// Strings are returned as is. If `to_s` returns something other than a
+/// string, the default `Object#to_s` representation (i.e. `#<Foo>`) is used
+/// instead.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::object::obj_as_string;
+///
+/// unsafe {
+///     let sym = rb_sys::rb_eval_string(":hello\0".as_ptr() as _);
+///     let string = obj_as_string(sym).unwrap();
+/// }
+/// ```
+pub unsafe fn obj_as_string(value: VALUE) -> Result<VALUE, RubyException> {
+    protect(|| rb_obj_as_string(value))
+}
+
+/// Calls the superclass implementation of the current method with `args`
+/// (akin to `super(*args)`, via `rb_call_super`), i.e. to fall back to the
+/// default behavior from a `method_missing` which does not handle a method.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// This must be called from a method defined in Rust which Ruby is currently
+/// running (i.e. not from a `protect` or a block). The values in `args` must
+/// be live Ruby objects.
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::object::call_super;
+/// use rb_sys::VALUE;
+/// use std::os::raw::c_int;
+///
+/// unsafe extern "C" fn method_missing(argc: c_int, argv: *const VALUE, _rb_self: VALUE) -> VALUE {
+///     let args = std::slice::from_raw_parts(argv, argc as usize);
+///
+///     // ... handle the methods we know about, and otherwise:
+///     call_super(args)
+/// }
+/// ```
+pub unsafe fn call_super(args: &[VALUE]) -> VALUE {
+    crate::rb_call_super(args.len() as c_int, args.as_ptr())
+}
+
+/// Raises the `NoMethodError` Ruby would raise for calling the undefined
+/// method `method` on `recv`, with the same message and `receiver`, by
+/// calling the original `BasicObject#method_missing`.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `recv` must be a live Ruby object.
+pub unsafe fn raise_no_method_error(recv: VALUE, method: &str) -> ! {
+    let method_missing = rb_id2sym(intern("method_missing"));
+    let unbound = rb_funcallv(
+        crate::rb_cBasicObject,
+        intern("instance_method"),
+        1,
+        &method_missing,
+    );
+    let bound = rb_funcallv(unbound, intern("bind"), 1, &recv);
+    let name = rb_id2sym(intern(method));
+
+    rb_funcallv(bound, intern("call"), 1, &name);
+
+    unreachable!("BasicObject#method_missing did not raise")
+}
+
+/// Returns the arity of the method `method` of `recv` (via
+/// `rb_obj_method_arity`, akin to `recv.method(method).arity`).
+///
+/// Methods with a fixed number of arguments return that number. Methods with
+/// optional or splat arguments return `-(required + 1)`, i.e. `-1` for
+/// `def foo(*args)` and `-2` for `def foo(a, b = 1)`. Fails with a
+/// `NameError` if `recv` does not respond to the method (including private
+/// ones). Methods which only exist via `respond_to_missing?` have an arity of
+/// `0`, rather than the `-1` of `Object#method`.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::object::method_arity;
+///
+/// unsafe {
+///     let string = rb_sys::rb_utf8_str_new("hello".as_ptr() as _, 5);
+///
+///     assert_eq!(method_arity(string, "upcase").unwrap(), -1);
+/// }
+/// ```
+pub unsafe fn method_arity(recv: VALUE, method: &str) -> Result<i32, RubyException> {
+    let id = intern(method);
+
+    protect(|| {
+        // `rb_obj_method_arity` returns 0 for missing methods, so raise the
+        // same `NameError` as `Object#method` instead.
+        if crate::rb_obj_respond_to(recv, id, 1) == 0 {
+            rb_obj_method(recv, rb_id2sym(id));
+        }
+
+        crate::rb_obj_method_arity(recv, id) as i32
+    })
+}
+
+/// A `VALUE` which is known to be frozen.
+///
+/// Since a frozen object can never be modified again, it is safe to cache and
+/// reuse. Note that freezing is shallow, so the objects it references may
+/// still be mutable (i.e. the elements of a frozen `Array`), which means a
+/// frozen object is not necessarily shareable between Ractors.
+#[cfg(feature = "stable-api")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct FrozenValue(VALUE);
+
+#[cfg(feature = "stable-api")]
+impl FrozenValue {
+    /// Wraps `value` if it is frozen, returning `None` otherwise. Immediates
+    /// (i.e. `nil`, `Integer`s and static `Symbol`s) are always frozen.
+    ///
+    /// # Safety
+    /// The Ruby VM must be initialized, and `value` must be a live Ruby object.
+    pub unsafe fn new(value: VALUE) -> Option<Self> {
+        use crate::StableApiDefinition;
+
+        let api = crate::stable_api::get_default();
+
+        if api.special_const_p(value) || api.frozen_p(value) {
+            Some(Self(value))
+        } else {
+            None
+        }
+    }
+
+    /// The underlying frozen object.
+    pub fn get(self) -> VALUE {
+        self.0
+    }
+}
+
+#[cfg(feature = "stable-api")]
+impl From<FrozenValue> for VALUE {
+    fn from(value: FrozenValue) -> Self {
+        value.0
+    }
+}
+
+/// Freezes `value` (akin to `value.freeze`), returning it as a
+/// [`FrozenValue`].
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `value` must be a live Ruby object.
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::object::{freeze, FrozenValue};
+///
+/// unsafe {
+///     let string = rb_sys::rb_utf8_str_new("hello".as_ptr() as _, 5);
+///     let frozen = freeze(string);
+///
+///     assert_eq!(FrozenValue::new(string), Some(frozen));
+/// }
+/// ```
+#[cfg(feature = "stable-api")]
+pub unsafe fn freeze(value: VALUE) -> FrozenValue {
+    FrozenValue(crate::rb_obj_freeze(value))
+}
+
+/// Returns a shallow copy of `value` (akin to `value.dup`, via `rb_obj_dup`).
+///
+/// The copy has the same class and instance variables and is initialized with
+/// `initialize_copy`, but it is never frozen and does not keep the singleton
+/// class of `value`. Immediate values (i.e. `nil` or a `Fixnum`) are returned
+/// as is. To copy only the contents of a `String`, use
+/// [`crate::string::str_dup`].
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `value` must be a live Ruby object.
+pub unsafe fn obj_dup(value: VALUE) -> VALUE {
+    crate::rb_obj_dup(value)
+}
+
+/// Allocates a new, uninitialized instance of `class` using its allocation
+/// function (akin to `class.allocate`, via `rb_obj_alloc`). `initialize` is
+/// not called.
+///
+/// This is the building block for allocation functions of classes backed by
+/// Rust data, which pair it with [`typed_data_wrap`].
+///
+/// Raises a `TypeError` if `class` is not a `Class`, is a singleton class, or
+/// has no allocation function (i.e. `Integer`).
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `class` must be a live Ruby object.
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::object::obj_alloc;
+///
+/// unsafe {
+///     let code = "Class.new { def initialize; @a = 1; end }\0";
+///     let klass = rb_sys::rb_eval_string(code.as_ptr() as _);
+///     let instance = obj_alloc(klass);
+/// }
+/// ```
+pub unsafe fn obj_alloc(class: VALUE) -> VALUE {
+    crate::rb_obj_alloc(class)
+}
+
+/// Wraps `data` in a new instance of `class`, which is marked, freed and
+/// sized by the callbacks of `data_type` (akin to
+/// `rb_data_typed_object_wrap`). The data can be read back with
+/// `rb_check_typeddata`, which also checks the type.
+///
+/// Use this from the allocation function of a class (see
+/// `rb_define_alloc_func`) with a null `data`, which is filled in by
+/// `initialize`, or directly to return Rust data to Ruby.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `data` must be null or valid for the callbacks of `data_type`, which take
+/// ownership of it.
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::object::typed_data_wrap;
+/// use rb_sys::rb_data_type_t;
+///
+/// unsafe extern "C" fn free_counter(data: *mut std::ffi::c_void) {
+///     drop(Box::from_raw(data as *mut u64));
+/// }
+///
+/// unsafe {
+///     let data_type: &'static mut rb_data_type_t = Box::leak(Box::new(std::mem::zeroed()));
+///     data_type.wrap_struct_name = "Counter\0".as_ptr() as _;
+///     data_type.function.dfree = Some(free_counter);
+///
+///     let counter = Box::into_raw(Box::new(0_u64));
+///     let obj = typed_data_wrap(rb_sys::rb_cObject, counter as _, data_type);
+/// }
+/// ```
+pub unsafe fn typed_data_wrap(
+    class: VALUE,
+    data: *mut c_void,
+    data_type: &'static rb_data_type_t,
+) -> VALUE {
+    crate::rb_data_typed_object_wrap(class, data, data_type)
+}
+
+/// Marks `value` as tainted (akin to `value.taint`, via `rb_obj_taint`),
+/// returning it.
+///
+/// Taint tracking has been a no-op since Ruby 2.7, and was removed in Ruby
+/// 3.2. On those versions this does nothing, so it is only useful for code
+/// which still supports Ruby 2.6; new code should not rely on taint at all.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `value` must be a live Ruby object.
+pub unsafe fn taint(value: VALUE) -> VALUE {
+    #[cfg(not(ruby_gte_3_2))]
+    #[allow(deprecated)]
+    return crate::rb_obj_taint(value);
+
+    #[cfg(ruby_gte_3_2)]
+    value
+}
+
+/// Clears the taint of `value` (akin to `value.untaint`, via
+/// `rb_obj_untaint`), returning it.
+///
+/// Like [`taint`], this does nothing on Ruby 2.7 and later.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `value` must be a live Ruby object.
+pub unsafe fn untaint(value: VALUE) -> VALUE {
+    #[cfg(not(ruby_gte_3_2))]
+    #[allow(deprecated)]
+    return crate::rb_obj_untaint(value);
+
+    #[cfg(ruby_gte_3_2)]
+    value
+}
+
+/// Checks if `value` is tainted (akin to `value.tainted?`, via
+/// `rb_obj_tainted`).
+///
+/// Nothing is tainted on Ruby 2.7 and later, so this always returns `false`
+/// there.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `value` must be a live Ruby object.
+pub unsafe fn is_tainted(value: VALUE) -> bool {
+    #[cfg(not(ruby_gte_3_2))]
+    #[allow(deprecated)]
+    return crate::rb_obj_tainted(value) == Qtrue as VALUE;
+
+    #[cfg(ruby_gte_3_2)]
+    {
+        let _ = value;
+        false
+    }
+}
+
+/// Raises a `FrozenError` if `value` is frozen (akin to `rb_check_frozen`,
+/// which is an inline function in Ruby's headers). Call this at the top of
+/// any function which mutates `value`.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `value` must be a live Ruby object.
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::object::check_frozen;
+/// use rb_sys::VALUE;
+///
+/// unsafe extern "C" fn clear(rb_self: VALUE) -> VALUE {
+///     check_frozen(rb_self);
+///     // ... it is now safe to mutate `rb_self`
+///     rb_self
+/// }
+/// ```
+#[cfg(feature = "stable-api")]
+pub unsafe fn check_frozen(value: VALUE) {
+    use crate::StableApiDefinition;
+
+    if crate::stable_api::get_default().frozen_p(value) {
+        crate::rb_error_frozen_object(value);
+    }
+}
+
+/// Returns the names of the instance variables set on `value`, including the
+/// leading `@` (akin to `value.instance_variables`), in the order they were
+/// first set.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `value` must be a live Ruby object.
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::object::instance_variables;
+///
+/// unsafe {
+///     let code = "o = Object.new; o.instance_variable_set(:@a, 1); o\0";
+///     let obj = rb_sys::rb_eval_string(code.as_ptr() as _);
+///
+///     assert_eq!(instance_variables(obj), ["@a"]);
+/// }
+/// ```
+#[cfg(feature = "stable-api")]
+pub unsafe fn instance_variables(value: VALUE) -> Vec<String> {
+    let ivars = crate::rb_obj_instance_variables(value);
+    let len = crate::RARRAY_LEN(ivars);
+    let mut names = Vec::with_capacity(len as usize);
+
+    for i in 0..len {
+        let name = crate::rb_sym2str(crate::rb_ary_entry(ivars, i));
+        let ptr = crate::RSTRING_PTR(name) as *const u8;
+        let bytes = std::slice::from_raw_parts(ptr, crate::RSTRING_LEN(name) as usize);
+
+        names.push(String::from_utf8_lossy(bytes).into_owned());
+    }
+
+    names
+}
+
+/// Compares `a` and `b` structurally: arrays are equal when their elements
+/// are, hashes when they have the same keys (found with `eql?`, in any order)
+/// with equal values, and anything else is compared with `eql?` (akin to
+/// `rb_eql`).
+///
+/// Unlike `==`, numbers of different classes are never equal (i.e. `1` and
+/// `1.0`), and strings must have compatible encodings. Unlike `eql?`, the
+/// contents of arrays and hashes are compared with these rules rather than
+/// with their own `eql?` and `hash` methods. Frozen-ness and the class of
+/// arrays and hashes are ignored. Recursive structures are supported.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::object::deep_eql;
+///
+/// unsafe {
+///     let a = rb_sys::rb_eval_string("[{ a: 'b' }, [1]]\0".as_ptr() as _);
+///     let b = rb_sys::rb_eval_string("[{ a: 'b' }, [1]]\0".as_ptr() as _);
+///     let c = rb_sys::rb_eval_string("[{ a: 'b' }, [1.0]]\0".as_ptr() as _);
+///
+///     assert!(deep_eql(a, b));
+///     assert!(!deep_eql(a, c));
+/// }
+/// ```
+#[cfg(feature = "stable-api")]
+pub unsafe fn deep_eql(a: VALUE, b: VALUE) -> bool {
+    deep_eql_in(a, b, &mut Vec::new())
+}
+
+/// Compares `a` and `b`, where `comparing` holds the containers which are
+/// being compared further up, to stop at cycles.
+#[cfg(feature = "stable-api")]
+unsafe fn deep_eql_in(a: VALUE, b: VALUE, comparing: &mut Vec<(VALUE, VALUE)>) -> bool {
+    use crate::ruby_value_type::{RUBY_T_ARRAY, RUBY_T_HASH};
+    use crate::{rb_ary_entry, rb_hash_size_num, RARRAY_LEN, RB_TYPE_P};
+
+    if a == b {
+        return true;
+    }
+
+    let arrays = RB_TYPE_P(a, RUBY_T_ARRAY) && RB_TYPE_P(b, RUBY_T_ARRAY);
+    let hashes = RB_TYPE_P(a, RUBY_T_HASH) && RB_TYPE_P(b, RUBY_T_HASH);
+
+    if !arrays && !hashes {
+        return crate::rb_eql(a, b) != 0;
+    }
+
+    if comparing.contains(&(a, b)) {
+        return true;
+    }
+
+    comparing.push((a, b));
+
+    let equal = if arrays {
+        let len = RARRAY_LEN(a);
+
+        len == RARRAY_LEN(b)
+            && (0..len).all(|i| deep_eql_in(rb_ary_entry(a, i), rb_ary_entry(b, i), comparing))
+    } else {
+        let keys = rb_funcallv(a, intern("keys"), 0, std::ptr::null());
+        let len = RARRAY_LEN(keys);
+
+        rb_hash_size_num(a) == rb_hash_size_num(b)
+            && (0..len).all(|i| {
+                let key = rb_ary_entry(keys, i);
+
+                match crate::hash::hash_lookup(b, key) {
+                    Some(value) => deep_eql_in(crate::rb_hash_aref(a, key), value, comparing),
+                    None => false,
+                }
+            })
+    };
+
+    comparing.pop();
+
+    equal
+}
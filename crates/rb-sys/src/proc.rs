@@ -0,0 +1,106 @@
+//! Helpers for creating Ruby `Proc`s backed by Rust closures.
+
+use crate::symbol::intern;
+use crate::{
+    rb_data_object_wrap, rb_eRuntimeError, rb_exc_new, rb_exc_raise, rb_ivar_set, rb_proc_new,
+    VALUE,
+};
+use std::any::Any;
+use std::ffi::c_void;
+use std::os::raw::c_int;
+use std::panic::{self, AssertUnwindSafe};
+
+/// Creates a `Proc` which calls `f` with the arguments it is called with
+/// (akin to `proc { |*args| ... }`), so a Rust closure can be passed where
+/// Ruby expects a block.
+///
+/// The closure is owned by the `Proc`, and is dropped when the `Proc` is
+/// garbage collected. If `f` panics, the panic is caught and raised as a
+/// `RuntimeError` instead, since it cannot unwind through Ruby.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// The `VALUE`s returned by `f` must be live Ruby objects, and `f` must not
+/// call the returned `Proc` itself, which would alias its mutable state. Any
+/// `VALUE`s captured by `f` are not marked by the GC, so they must be kept
+/// alive some other way (i.e. with `rb_gc_register_address`).
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::proc::proc_new;
+/// use rb_sys::{rb_ary_new, rb_ary_push, rb_funcall_with_block, VALUE};
+///
+/// unsafe {
+///     let doubled = proc_new(|args: &[VALUE]| {
+///         let n = rb_sys::rb_num2long(args[0]);
+///         rb_sys::rb_int2inum((n * 2) as _)
+///     });
+///
+///     let array = rb_ary_new();
+///     rb_ary_push(array, rb_sys::rb_int2inum(1));
+///     let id = rb_sys::symbol::intern("map");
+///     rb_funcall_with_block(array, id, 0, std::ptr::null(), doubled);
+/// }
+/// ```
+pub unsafe fn proc_new<F>(f: F) -> VALUE
+where
+    F: FnMut(&[VALUE]) -> VALUE + 'static,
+{
+    unsafe extern "C" fn call<F>(
+        _yielded_arg: VALUE,
+        callback_arg: VALUE,
+        argc: c_int,
+        argv: *const VALUE,
+        _blockarg: VALUE,
+    ) -> VALUE
+    where
+        F: FnMut(&[VALUE]) -> VALUE,
+    {
+        let f = &mut *(callback_arg as *mut F);
+        let args = if argc == 0 || argv.is_null() {
+            &[]
+        } else {
+            std::slice::from_raw_parts(argv, argc as usize)
+        };
+
+        match panic::catch_unwind(AssertUnwindSafe(|| f(args))) {
+            Ok(value) => value,
+            Err(payload) => raise_panic(payload),
+        }
+    }
+
+    unsafe extern "C" fn free<F>(ptr: *mut c_void) {
+        drop(Box::from_raw(ptr as *mut F));
+    }
+
+    let closure = Box::into_raw(Box::new(f));
+
+    // The closure is owned by a hidden data object, so it is freed even if
+    // creating the proc fails. Ruby only uses the callback argument as an
+    // opaque pointer, so it does not keep the owner alive by itself.
+    let owner = rb_data_object_wrap(0, closure as *mut c_void, None, Some(free::<F>));
+    let proc = rb_proc_new(Some(call::<F>), closure as VALUE);
+    rb_ivar_set(proc, intern("__rb_sys_closure__"), owner);
+
+    proc
+}
+
+/// Raises the panic `payload` as a `RuntimeError`, dropping it first since
+/// raising `longjmp`s past any live destructors.
+#[allow(unreachable_code)]
+unsafe fn raise_panic(payload: Box<dyn Any + Send>) -> ! {
+    let message = if let Some(message) = payload.downcast_ref::<&str>() {
+        format!("panic in Rust proc: {}", message)
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        format!("panic in Rust proc: {}", message)
+    } else {
+        "panic in Rust proc".to_owned()
+    };
+    drop(payload);
+
+    let exception = rb_exc_new(rb_eRuntimeError, message.as_ptr() as _, message.len() as _);
+    drop(message);
+
+    rb_exc_raise(exception);
+    unreachable!()
+}
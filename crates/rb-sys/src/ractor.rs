@@ -0,0 +1,38 @@
+//! Helpers for sharing objects between Ractors.
+
+use crate::{rb_ractor_make_shareable, rb_ractor_shareable_p_continue, VALUE};
+
+/// Checks if `value` can be sent to another Ractor without copying (akin to
+/// `Ractor.shareable?(value)`), i.e. it is deeply frozen, or is a module,
+/// class or Ractor.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::ractor::ractor_shareable;
+///
+/// unsafe {
+///     let value = rb_sys::rb_eval_string("[1, :two].freeze\0".as_ptr() as _);
+///
+///     assert!(ractor_shareable(value));
+/// }
+/// ```
+pub unsafe fn ractor_shareable(value: VALUE) -> bool {
+    // `rb_ractor_shareable_p` is an inline function, which checks the cached
+    // shareable flag before falling back to this (which checks it too).
+    rb_ractor_shareable_p_continue(value)
+}
+
+/// Makes `value` shareable by deeply freezing it (akin to
+/// `Ractor.make_shareable(value)`), and returns it.
+///
+/// Raises a `Ractor::Error` if some part of it can not be made shareable
+/// (i.e. a `Proc` whose `self` is not shareable).
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+pub unsafe fn make_shareable(value: VALUE) -> VALUE {
+    rb_ractor_make_shareable(value)
+}
@@ -28,6 +28,81 @@ pub extern "C" fn rb_tr_abi_version() -> *const std::os::raw::c_char {
     crate::TRUFFLERUBY_ABI_VERSION.as_ptr() as *const _
 }
 
+/// The error returned by [`check_abi`] when an extension is loaded by a Ruby
+/// with a different ABI version than the one it was compiled against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AbiMismatch {
+    /// The `(major, minor)` API version the extension was compiled against.
+    pub compiled: (u8, u8),
+    /// The `(major, minor)` API version of the running Ruby.
+    pub runtime: (u8, u8),
+}
+
+impl std::fmt::Display for AbiMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "extension was compiled for Ruby {}.{}, but was loaded by Ruby {}.{} (try recompiling it with the current Ruby)",
+            self.compiled.0, self.compiled.1, self.runtime.0, self.runtime.1
+        )
+    }
+}
+
+impl std::error::Error for AbiMismatch {}
+
+/// Checks that the running Ruby has the same ABI version as the Ruby that
+/// this crate was compiled against.
+///
+/// Loading an extension into the wrong Ruby usually results in confusing
+/// crashes, so calling this first thing in your `Init_*` function lets you
+/// fail with a clear error instead. Raise a `LoadError` rather than panicking,
+/// since a panic must not unwind out of `Init_*` into Ruby's `require`.
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::exception::exception_new;
+///
+/// #[no_mangle]
+/// unsafe extern "C" fn Init_my_gem() {
+///     if let Err(e) = rb_sys::check_abi() {
+///         let message = e.to_string();
+///         let error = exception_new(rb_sys::rb_eLoadError, &message);
+///
+///         // Raising skips destructors, so `message` would leak.
+///         drop(message);
+///         rb_sys::rb_exc_raise(error);
+///     }
+///
+///     // ...
+/// }
+/// ```
+pub fn check_abi() -> Result<(), AbiMismatch> {
+    #[cfg(ruby_engine = "truffleruby")]
+    return Ok(());
+
+    #[cfg(not(ruby_engine = "truffleruby"))]
+    {
+        let compiled = (
+            crate::RUBY_API_VERSION_MAJOR as u8,
+            crate::RUBY_API_VERSION_MINOR as u8,
+        );
+        // SAFETY: `ruby_api_version` is a constant in libruby, which is always
+        // present when an extension is loaded.
+        let runtime = unsafe {
+            (
+                crate::ruby_api_version[0] as u8,
+                crate::ruby_api_version[1] as u8,
+            )
+        };
+
+        if compiled == runtime {
+            Ok(())
+        } else {
+            Err(AbiMismatch { compiled, runtime })
+        }
+    }
+}
+
 #[deprecated(
     since = "0.9.102",
     note = "You no longer need to invoke this macro, the `ruby_abi_version` function is defined automatically."
@@ -0,0 +1,255 @@
+//! A typed wrapper around `rb_scan_args` (and `rb_scan_args_kw`), for parsing
+//! the arguments of methods defined with an arity of -1.
+
+use crate::{rb_scan_args, Qnil, VALUE};
+use std::os::raw::c_int;
+
+/// The most output variables `rb_scan_args` can write: 9 required, 9
+/// optional, plus the splat, keywords and block.
+const MAX_VARS: usize = 9 + 9 + 3;
+
+/// Declares the arguments a method accepts (akin to the format string of
+/// `rb_scan_args`), in the order Ruby expects them: required, optional, splat,
+/// keywords, then block.
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::scan_args::ScanArgs;
+/// use rb_sys::VALUE;
+/// use std::os::raw::c_int;
+///
+/// // def greet(name, greeting = nil, *rest, **opts, &block)
+/// unsafe extern "C" fn greet(argc: c_int, argv: *const VALUE, _rb_self: VALUE) -> VALUE {
+///     let args = ScanArgs::new()
+///         .required(1)
+///         .optional(1)
+///         .splat()
+///         .keywords()
+///         .block()
+///         .scan(argc, argv);
+///
+///     args.optional[0].unwrap_or(args.required[0])
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ScanArgs {
+    required: u8,
+    optional: u8,
+    splat: bool,
+    keywords: bool,
+    block: bool,
+    kw_flag: Option<KeywordFlag>,
+}
+
+/// How `rb_scan_args_kw` decides whether a trailing `Hash` holds the keyword
+/// arguments (see [`ScanArgs::kw_flag`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeywordFlag {
+    /// Keywords are only taken from a call which passed keywords
+    /// (`RB_SCAN_ARGS_PASS_CALLED_KEYWORDS`).
+    PassCalledKeywords = 0,
+    /// The trailing `Hash` is always the keywords, i.e. when the arguments
+    /// were forwarded from a call with keywords (`RB_SCAN_ARGS_KEYWORDS`).
+    Keywords = 1,
+    /// A trailing `Hash` is the keywords, even if it was passed positionally,
+    /// as before Ruby 3.0 (`RB_SCAN_ARGS_LAST_HASH_KEYWORDS`).
+    LastHashKeywords = 3,
+}
+
+/// The arguments parsed by [`ScanArgs::scan`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScannedArgs {
+    /// The required positional arguments.
+    pub required: Vec<VALUE>,
+    /// The optional positional arguments, `None` when not given. An explicit
+    /// `nil` is `Some(Qnil)`.
+    pub optional: Vec<Option<VALUE>>,
+    /// The remaining positional arguments as an `Array`, if a splat was
+    /// declared (the array is empty when there are none).
+    pub splat: Option<VALUE>,
+    /// The keyword arguments as a `Hash`, if keywords were declared and given.
+    pub keywords: Option<VALUE>,
+    /// The block as a `Proc`, if a block was declared and given.
+    pub block: Option<VALUE>,
+}
+
+impl ScanArgs {
+    /// Accepts no arguments at all.
+    pub const fn new() -> Self {
+        Self {
+            required: 0,
+            optional: 0,
+            splat: false,
+            keywords: false,
+            block: false,
+            kw_flag: None,
+        }
+    }
+
+    /// Requires `count` leading positional arguments.
+    ///
+    /// # Panics
+    /// If `count` is greater than 9, which `rb_scan_args` does not support.
+    pub fn required(mut self, count: u8) -> Self {
+        assert!(
+            count <= 9,
+            "rb_scan_args supports at most 9 required arguments"
+        );
+        self.required = count;
+        self
+    }
+
+    /// Accepts `count` optional positional arguments after the required ones.
+    ///
+    /// # Panics
+    /// If `count` is greater than 9, which `rb_scan_args` does not support.
+    pub fn optional(mut self, count: u8) -> Self {
+        assert!(
+            count <= 9,
+            "rb_scan_args supports at most 9 optional arguments"
+        );
+        self.optional = count;
+        self
+    }
+
+    /// Collects any remaining positional arguments into an `Array`.
+    pub fn splat(mut self) -> Self {
+        self.splat = true;
+        self
+    }
+
+    /// Accepts keyword arguments, collected into a `Hash`.
+    pub fn keywords(mut self) -> Self {
+        self.keywords = true;
+        self
+    }
+
+    /// Accepts a block, converted to a `Proc`.
+    pub fn block(mut self) -> Self {
+        self.block = true;
+        self
+    }
+
+    /// Scans with `rb_scan_args_kw` and `flag`, to choose whether a trailing
+    /// `Hash` passed positionally is treated as the keywords.
+    ///
+    /// Without a flag, `rb_scan_args` follows the keyword semantics of the
+    /// running Ruby: from Ruby 3.0 only keywords passed as keywords are
+    /// collected, while Ruby 2.7 also takes a positional `Hash` (with a
+    /// deprecation warning). Ruby 2.6 and older, which lack
+    /// `rb_scan_args_kw`, always treat a trailing `Hash` as the keywords
+    /// (see `ruby_have_rb_scan_args_optional_hash`) and ignore `flag`.
+    pub fn kw_flag(mut self, flag: KeywordFlag) -> Self {
+        self.kw_flag = Some(flag);
+        self
+    }
+
+    /// Parses the arguments a method was called with. Raises an
+    /// `ArgumentError` if the number of positional arguments does not match.
+    ///
+    /// # Safety
+    /// The Ruby VM must be initialized and the current thread must hold the
+    /// GVL. `argc` and `argv` must be the arguments Ruby passed to the
+    /// currently running method (which is needed to find the block and
+    /// keywords). If this raises, it `longjmp`s, so no Rust values with
+    /// destructors may be live in the calling frames.
+    pub unsafe fn scan(self, argc: c_int, argv: *const VALUE) -> ScannedArgs {
+        let format = self.format();
+        let mut vars = [Qnil as VALUE; MAX_VARS];
+        let v = vars.as_mut_ptr();
+
+        // `rb_scan_args` only writes to as many variables as the format asks
+        // for, and ignores the rest.
+        macro_rules! scan_into_vars {
+            ($scan:expr, $($arg:expr),*) => {
+                $scan(
+                    $($arg,)*
+                    format.as_ptr() as _,
+                    v,
+                    v.add(1),
+                    v.add(2),
+                    v.add(3),
+                    v.add(4),
+                    v.add(5),
+                    v.add(6),
+                    v.add(7),
+                    v.add(8),
+                    v.add(9),
+                    v.add(10),
+                    v.add(11),
+                    v.add(12),
+                    v.add(13),
+                    v.add(14),
+                    v.add(15),
+                    v.add(16),
+                    v.add(17),
+                    v.add(18),
+                    v.add(19),
+                    v.add(20),
+                )
+            };
+        }
+
+        let given = match self.kw_flag {
+            #[cfg(ruby_gte_2_7)]
+            Some(flag) => scan_into_vars!(crate::rb_scan_args_kw, flag as c_int, argc, argv),
+            _ => scan_into_vars!(rb_scan_args, argc, argv),
+        };
+
+        // Optional arguments which were not given are set to `nil`, so use the
+        // number of positional arguments to tell them apart from an explicit
+        // `nil`.
+        let given_optional = (given as usize).saturating_sub(self.required as usize);
+        let mut vars = vars.iter().copied();
+        let non_nil = |value: VALUE| value != Qnil as VALUE;
+
+        ScannedArgs {
+            required: vars.by_ref().take(self.required as usize).collect(),
+            optional: vars
+                .by_ref()
+                .take(self.optional as usize)
+                .enumerate()
+                .map(|(i, value)| Some(value).filter(|_| i < given_optional))
+                .collect(),
+            splat: if self.splat { vars.next() } else { None },
+            keywords: if self.keywords {
+                vars.next().filter(|v| non_nil(*v))
+            } else {
+                None
+            },
+            block: if self.block {
+                vars.next().filter(|v| non_nil(*v))
+            } else {
+                None
+            },
+        }
+    }
+
+    /// The nul-terminated `rb_scan_args` format string (i.e. `"11*:&"`). Kept
+    /// on the stack, since `rb_scan_args` may raise.
+    fn format(&self) -> [u8; 8] {
+        let mut format = [0u8; 8];
+        let mut len = 0;
+        let mut push = |byte: u8| {
+            format[len] = byte;
+            len += 1;
+        };
+
+        push(b'0' + self.required);
+
+        if self.optional > 0 {
+            push(b'0' + self.optional);
+        }
+        if self.splat {
+            push(b'*');
+        }
+        if self.keywords {
+            push(b':');
+        }
+        if self.block {
+            push(b'&');
+        }
+
+        format
+    }
+}
@@ -0,0 +1,116 @@
+//! Helpers for cooperating with the `Fiber` scheduler (`Fiber.set_scheduler`),
+//! so native code which blocks does not block the other fibers on the thread.
+//!
+//! Each helper takes the `scheduler` returned by [`current_scheduler`], and
+//! calls the matching hook on it (i.e. [`scheduler_sleep`] calls
+//! `scheduler.kernel_sleep`). When there is no scheduler, block as usual.
+
+use crate::{
+    rb_fiber_scheduler_block, rb_fiber_scheduler_current, rb_fiber_scheduler_io_wait,
+    rb_fiber_scheduler_kernel_sleep, rb_fiber_scheduler_unblock, rb_float_new, rb_int2inum, Qnil,
+    VALUE,
+};
+use std::time::Duration;
+
+/// Returns the scheduler of the current fiber (akin to
+/// `Fiber.current_scheduler`), or `None` if the fiber is blocking or no
+/// scheduler is set for the current thread.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::scheduler::{current_scheduler, scheduler_sleep};
+/// use std::time::Duration;
+///
+/// unsafe {
+///     match current_scheduler() {
+///         Some(scheduler) => {
+///             scheduler_sleep(scheduler, Some(Duration::from_millis(10)));
+///         }
+///         None => std::thread::sleep(Duration::from_millis(10)),
+///     }
+/// }
+/// ```
+pub unsafe fn current_scheduler() -> Option<VALUE> {
+    let scheduler = rb_fiber_scheduler_current();
+
+    if scheduler == Qnil as VALUE {
+        None
+    } else {
+        Some(scheduler)
+    }
+}
+
+/// Suspends the current fiber for `duration`, or until it is woken up when
+/// `None` (akin to `scheduler.kernel_sleep(duration)`).
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `scheduler` must be the scheduler of the current fiber.
+pub unsafe fn scheduler_sleep(scheduler: VALUE, duration: Option<Duration>) -> VALUE {
+    let duration = timeout_value(duration);
+
+    rb_fiber_scheduler_kernel_sleep(scheduler, duration)
+}
+
+/// Suspends the current fiber until `io` is ready for `events` (a bitmask of
+/// `RUBY_IO_READABLE`, `RUBY_IO_WRITABLE` and `RUBY_IO_PRIORITY`), or until
+/// `timeout` passes (akin to `scheduler.io_wait(io, events, timeout)`).
+/// Returns the events which are ready, or a falsy value on timeout.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `scheduler` must be the scheduler of the current fiber, and `io` a Ruby
+/// `IO`.
+pub unsafe fn scheduler_io_wait(
+    scheduler: VALUE,
+    io: VALUE,
+    events: u32,
+    timeout: Option<Duration>,
+) -> VALUE {
+    let timeout = timeout_value(timeout);
+
+    rb_fiber_scheduler_io_wait(scheduler, io, rb_int2inum(events as _), timeout)
+}
+
+/// Suspends the current fiber on `blocker` until it is passed to
+/// [`scheduler_unblock`], or until `timeout` passes (akin to
+/// `scheduler.block(blocker, timeout)`). Use this to wait for work done
+/// outside of Ruby (i.e. on another native thread) without blocking the
+/// other fibers.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `scheduler` must be the scheduler of the current fiber, and `blocker` a
+/// live Ruby object which identifies what the fiber is waiting for.
+pub unsafe fn scheduler_block(
+    scheduler: VALUE,
+    blocker: VALUE,
+    timeout: Option<Duration>,
+) -> VALUE {
+    let timeout = timeout_value(timeout);
+
+    rb_fiber_scheduler_block(scheduler, blocker, timeout)
+}
+
+/// Resumes `fiber`, which is blocked on `blocker` by [`scheduler_block`]
+/// (akin to `scheduler.unblock(blocker, fiber)`). Unlike the other hooks,
+/// this may be called from a thread other than the one the scheduler belongs
+/// to, as long as it holds the GVL.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `fiber` must be a Ruby `Fiber`.
+pub unsafe fn scheduler_unblock(scheduler: VALUE, blocker: VALUE, fiber: VALUE) -> VALUE {
+    rb_fiber_scheduler_unblock(scheduler, blocker, fiber)
+}
+
+/// Converts a timeout to seconds as a Ruby `Float`, or `nil` for none.
+unsafe fn timeout_value(timeout: Option<Duration>) -> VALUE {
+    match timeout {
+        Some(timeout) => rb_float_new(timeout.as_secs_f64()),
+        None => Qnil as VALUE,
+    }
+}
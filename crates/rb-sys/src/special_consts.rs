@@ -29,3 +29,82 @@ impl Into<VALUE> for ruby_special_consts {
         self as VALUE
     }
 }
+
+// The predicates below only look at the bits of a `VALUE`, without reading
+// Ruby's memory, so unlike their counterparts in `macros` they can be `const`.
+
+/// Checks if `obj` is `nil` (akin to `NIL_P`), in a `const` context.
+///
+/// ```
+/// use rb_sys::special_consts::*;
+///
+/// const IS_NIL: bool = nil_p(Qnil as _);
+///
+/// assert!(IS_NIL);
+/// ```
+#[inline(always)]
+pub const fn nil_p(obj: VALUE) -> bool {
+    obj == Qnil as VALUE
+}
+
+/// Checks if `obj` is truthy, i.e. neither `false` nor `nil` (akin to
+/// `RB_TEST`), in a `const` context.
+#[inline(always)]
+pub const fn rb_test(obj: VALUE) -> bool {
+    obj & !(Qnil as VALUE) != 0
+}
+
+/// Checks if `obj` is a Fixnum (akin to `FIXNUM_P`), in a `const` context.
+#[inline(always)]
+pub const fn fixnum_p(obj: VALUE) -> bool {
+    obj & FIXNUM_FLAG as VALUE != 0
+}
+
+/// Checks if `obj` is a static symbol (akin to `STATIC_SYM_P`), in a `const`
+/// context.
+#[inline(always)]
+pub const fn static_sym_p(obj: VALUE) -> bool {
+    let mask = !(VALUE::MAX << ruby_special_consts::RUBY_SPECIAL_SHIFT as VALUE);
+
+    obj & mask == SYMBOL_FLAG as VALUE
+}
+
+/// Checks if `obj` is a flonum (akin to `FLONUM_P`), in a `const` context.
+/// Always false on platforms without flonums.
+#[inline(always)]
+pub const fn flonum_p(obj: VALUE) -> bool {
+    #[cfg(ruby_use_flonum = "true")]
+    return obj & FLONUM_MASK as VALUE == FLONUM_FLAG as VALUE;
+
+    #[cfg(not(ruby_use_flonum = "true"))]
+    {
+        let _ = obj;
+        false
+    }
+}
+
+/// Checks if `obj` is an immediate (i.e. a Fixnum, flonum or static symbol)
+/// (akin to `IMMEDIATE_P`), in a `const` context.
+#[inline(always)]
+pub const fn immediate_p(obj: VALUE) -> bool {
+    obj & IMMEDIATE_MASK as VALUE != 0
+}
+
+/// Checks if `obj` is a special constant, i.e. an immediate, `false` or `nil`
+/// (akin to `SPECIAL_CONST_P`), in a `const` context.
+///
+/// ```
+/// use rb_sys::special_consts::*;
+///
+/// const SPECIAL: [bool; 3] = [
+///     special_const_p(Qnil as _),
+///     special_const_p(Qfalse as _),
+///     special_const_p(Qtrue as _),
+/// ];
+///
+/// assert_eq!(SPECIAL, [true, true, true]);
+/// ```
+#[inline(always)]
+pub const fn special_const_p(obj: VALUE) -> bool {
+    immediate_p(obj) || !rb_test(obj)
+}
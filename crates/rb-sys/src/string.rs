@@ -0,0 +1,614 @@
+//! Helpers for building and mutating Ruby strings.
+
+use crate::exception::{protect, RubyException};
+use crate::utils::cstring_or_raise;
+use crate::{
+    rb_enc_str_asciionly_p, rb_str_cat, rb_str_encode, rb_str_modify_expand, rb_str_new, Qnil,
+    VALUE,
+};
+#[cfg(feature = "stable-api")]
+use crate::{rb_str_modify, rb_str_resize};
+
+/// Ensures `string` has room for at least `additional` more bytes without
+/// reallocating (via `rb_str_modify_expand`).
+///
+/// This also makes the string independent of any shared buffer, so it is safe
+/// to write into afterwards. Raises a `FrozenError` if `string` is frozen.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `string` must be a Ruby `String`.
+pub unsafe fn str_reserve(string: VALUE, additional: usize) {
+    rb_str_modify_expand(string, additional as _);
+}
+
+/// Appends `bytes` to `string`, growing its capacity geometrically when it
+/// runs out of room. Returns `string`.
+///
+/// Pair with [`str_reserve`] when the final size is known up front to avoid
+/// any intermediate reallocations.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `string` must be a Ruby `String`.
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::string::{str_append_bytes, str_reserve};
+///
+/// unsafe {
+///     let string = rb_sys::rb_str_new(std::ptr::null(), 0);
+///     str_reserve(string, 1024);
+///
+///     for _ in 0..16 {
+///         str_append_bytes(string, &[b'a'; 64]);
+///     }
+/// }
+/// ```
+pub unsafe fn str_append_bytes(string: VALUE, bytes: &[u8]) -> VALUE {
+    rb_str_cat(string, bytes.as_ptr() as _, bytes.len() as _)
+}
+
+/// Appends the ASCII text `ascii` to `string` (akin to
+/// `rb_str_buf_cat_ascii`). Returns `string`.
+///
+/// Unlike [`str_append_bytes`] (and `rb_str_cat`), this keeps the coderange
+/// Ruby has cached for `string`, so an ASCII-only string does not need to be
+/// scanned again by later encoding checks (i.e. [`is_ascii_only`]). Raises an
+/// `Encoding::CompatibilityError` if the encoding of `string` is not
+/// ASCII-compatible (i.e. UTF-16).
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `string` must be a Ruby `String`. `ascii` must only contain ASCII, which
+/// is checked in debug builds.
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::string::str_cat_ascii;
+///
+/// unsafe {
+///     let json = rb_sys::rb_utf8_str_new("{".as_ptr() as _, 1);
+///     str_cat_ascii(json, "\"key\":1}");
+/// }
+/// ```
+pub unsafe fn str_cat_ascii(string: VALUE, ascii: &str) -> VALUE {
+    debug_assert!(ascii.is_ascii(), "not ASCII: {:?}", ascii);
+
+    crate::rb_enc_str_buf_cat(
+        string,
+        ascii.as_ptr() as _,
+        ascii.len() as _,
+        crate::rb_usascii_encoding(),
+    )
+}
+
+/// Replaces the entire contents of `string` with `bytes`, reusing its buffer
+/// when it is large enough. Returns `string`.
+///
+/// The string keeps its encoding, and raises a `FrozenError` if it is frozen.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `string` must be a Ruby `String`, and `bytes` must not point into its
+/// buffer (which may be reallocated).
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::string::str_set_bytes;
+///
+/// unsafe {
+///     let string = rb_sys::rb_utf8_str_new("hello".as_ptr() as _, 5);
+///     str_set_bytes(string, b"goodbye");
+/// }
+/// ```
+#[cfg(feature = "stable-api")]
+pub unsafe fn str_set_bytes(string: VALUE, bytes: &[u8]) -> VALUE {
+    // Makes sure the buffer is not shared with another string (or frozen),
+    // and clears the cached coderange since the contents will change.
+    rb_str_modify(string);
+    rb_str_resize(string, bytes.len() as _);
+
+    let ptr = crate::RSTRING_PTR(string) as *mut u8;
+    std::ptr::copy_nonoverlapping(bytes.as_ptr(), ptr, bytes.len());
+
+    string
+}
+
+/// Checks if the bytes of `string` are valid in its encoding (akin to
+/// `String#valid_encoding?`).
+///
+/// Check this before treating the bytes of a UTF-8 string as a `&str`, since
+/// Ruby strings may contain invalid byte sequences. The result is cached on
+/// the string, so repeated checks are cheap.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `string` must be a Ruby `String`.
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::string::str_valid_encoding;
+///
+/// unsafe {
+///     let string = rb_sys::rb_utf8_str_new(b"\xff".as_ptr() as _, 1);
+///
+///     assert!(!str_valid_encoding(string));
+/// }
+/// ```
+pub unsafe fn str_valid_encoding(string: VALUE) -> bool {
+    coderange(string) != CodeRange::Broken
+}
+
+/// Checks if `string` only contains ASCII characters (akin to
+/// `String#ascii_only?`), in which case its bytes are also valid UTF-8.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `string` must be a Ruby `String`.
+pub unsafe fn is_ascii_only(string: VALUE) -> bool {
+    rb_enc_str_asciionly_p(string) != 0
+}
+
+/// How the bytes of a string relate to its encoding (its "coderange"), as
+/// analyzed and cached by Ruby.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CodeRange {
+    /// Only ASCII characters, so the bytes are also valid UTF-8.
+    SevenBit,
+    /// Valid in the string's encoding, with some non-ASCII characters.
+    Valid,
+    /// Contains byte sequences which are invalid in the string's encoding.
+    Broken,
+    /// Ruby has not analyzed the string.
+    Unknown,
+}
+
+/// Returns the coderange of `string`, scanning it if Ruby has not done so
+/// already (via `rb_enc_str_coderange`).
+///
+/// The result is cached on the string until it is modified, so this is cheap
+/// to call repeatedly. It allows fast paths for ASCII-only strings, i.e.
+/// skipping UTF-8 validation for [`CodeRange::SevenBit`].
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `string` must be a Ruby `String`.
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::string::{coderange, CodeRange};
+///
+/// unsafe {
+///     let string = rb_sys::rb_utf8_str_new("hello".as_ptr() as _, 5);
+///
+///     assert_eq!(coderange(string), CodeRange::SevenBit);
+/// }
+/// ```
+pub unsafe fn coderange(string: VALUE) -> CodeRange {
+    #[cfg(ruby_gte_3_0)]
+    return {
+        use crate::ruby_coderange_type::*;
+        use std::os::raw::c_int;
+
+        match crate::rb_enc_str_coderange(string) {
+            cr if cr == RUBY_ENC_CODERANGE_7BIT as c_int => CodeRange::SevenBit,
+            cr if cr == RUBY_ENC_CODERANGE_VALID as c_int => CodeRange::Valid,
+            cr if cr == RUBY_ENC_CODERANGE_BROKEN as c_int => CodeRange::Broken,
+            _ => CodeRange::Unknown,
+        }
+    };
+
+    // Older Rubies only define the coderange flags as macros, which are not
+    // part of the bindings.
+    #[cfg(not(ruby_gte_3_0))]
+    {
+        if is_ascii_only(string) {
+            return CodeRange::SevenBit;
+        }
+
+        let valid = crate::rb_funcallv(
+            string,
+            crate::symbol::intern("valid_encoding?"),
+            0,
+            std::ptr::null(),
+        );
+
+        if valid == crate::Qtrue as VALUE {
+            CodeRange::Valid
+        } else {
+            CodeRange::Broken
+        }
+    }
+}
+
+/// Splits `string` on each occurrence of `separator` (via `rb_str_split`,
+/// akin to `string.split(separator)`), returning a Ruby `Array` of strings.
+///
+/// As with `String#split`, a separator of `" "` splits on runs of whitespace
+/// and ignores leading whitespace, and trailing empty strings are removed.
+/// Ruby creates the separator as a binary string, so a non-ASCII separator
+/// raises an `Encoding::CompatibilityError` unless `string` is ASCII-only or
+/// binary. Raises an `ArgumentError` if `separator` contains a null byte.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `string` must be a Ruby `String`.
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::string::str_split;
+///
+/// unsafe {
+///     let string = rb_sys::rb_utf8_str_new("a,b,c".as_ptr() as _, 5);
+///     let parts = str_split(string, ",");
+/// }
+/// ```
+pub unsafe fn str_split(string: VALUE, separator: &str) -> VALUE {
+    let separator = cstring_or_raise(separator.to_owned());
+
+    crate::rb_str_split(string, separator.as_ptr())
+}
+
+/// Returns a copy of `string` with all characters in uppercase (akin to
+/// `String#upcase`), using the Unicode case mapping for Unicode encodings.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `string` must be a Ruby `String`.
+pub unsafe fn str_upcase(string: VALUE) -> VALUE {
+    crate::rb_funcallv(string, crate::symbol::intern("upcase"), 0, std::ptr::null())
+}
+
+/// Returns a copy of `string` with all characters in lowercase (akin to
+/// `String#downcase`), using the Unicode case mapping for Unicode encodings.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `string` must be a Ruby `String`.
+pub unsafe fn str_downcase(string: VALUE) -> VALUE {
+    crate::rb_funcallv(
+        string,
+        crate::symbol::intern("downcase"),
+        0,
+        std::ptr::null(),
+    )
+}
+
+/// Returns a copy of `string` with the first match of the `Regexp` `pattern`
+/// replaced by the result of `replace` (akin to `string.sub(pattern) { ... }`).
+///
+/// See [`str_gsub`] for how `replace` is called.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `string` must be a Ruby `String` and `pattern` a Ruby `Regexp`.
+#[cfg(feature = "stable-api")]
+pub unsafe fn str_sub<F>(string: VALUE, pattern: VALUE, replace: F) -> VALUE
+where
+    F: FnMut(VALUE) -> VALUE,
+{
+    substitute(string, pattern, replace, false)
+}
+
+/// Returns a copy of `string` with every match of the `Regexp` `pattern`
+/// replaced by the result of `replace` (akin to `string.gsub(pattern) { ... }`).
+///
+/// `replace` is called with the `MatchData` of each match, in order, and
+/// returns the replacement, which is converted with `to_s` if it is not a
+/// `String`. Use `rb_reg_nth_match` to get the matched text or its groups.
+/// Each `MatchData` is a new object, so it is safe to keep after `replace`
+/// returns.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `string` must be a Ruby `String` and `pattern` a Ruby `Regexp`.
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::string::str_gsub;
+///
+/// unsafe {
+///     let string = rb_sys::rb_utf8_str_new("a1b22".as_ptr() as _, 5);
+///     let pattern = rb_sys::rb_reg_new("\\d+".as_ptr() as _, 3, 0);
+///
+///     // => "a1b2"
+///     let lengths = str_gsub(string, pattern, |m| {
+///         let digits = rb_sys::rb_reg_nth_match(0, m);
+///         rb_sys::rb_str_length(digits)
+///     });
+/// }
+/// ```
+#[cfg(feature = "stable-api")]
+pub unsafe fn str_gsub<F>(string: VALUE, pattern: VALUE, replace: F) -> VALUE
+where
+    F: FnMut(VALUE) -> VALUE,
+{
+    substitute(string, pattern, replace, true)
+}
+
+#[cfg(feature = "stable-api")]
+unsafe fn substitute<F>(string: VALUE, pattern: VALUE, mut replace: F, global: bool) -> VALUE
+where
+    F: FnMut(VALUE) -> VALUE,
+{
+    use crate::{rb_backref_get, rb_reg_nth_match, rb_str_append, rb_str_subseq, RSTRING_LEN};
+
+    let len = RSTRING_LEN(string);
+    let result = rb_str_subseq(string, 0, 0);
+    let mut copied = 0;
+    let mut pos = 0;
+
+    while pos <= len {
+        let start = crate::rb_reg_search(pattern, string, pos, 0);
+
+        if start < 0 {
+            break;
+        }
+
+        let start = start as _;
+        let matched = rb_backref_get();
+        // Makes the next search allocate a new `MatchData` instead of reusing
+        // this one.
+        crate::rb_match_busy(matched);
+        let end = start + RSTRING_LEN(rb_reg_nth_match(0, matched));
+
+        rb_str_append(result, rb_str_subseq(string, copied, start - copied));
+        rb_str_append(result, crate::rb_obj_as_string(replace(matched)));
+        copied = end;
+
+        if !global {
+            break;
+        }
+
+        pos = if end > start {
+            end
+        } else {
+            // Steps over the next character after an empty match, so the
+            // search makes progress.
+            end + char_len(string, end)
+        };
+    }
+
+    if copied < len {
+        rb_str_append(result, rb_str_subseq(string, copied, len - copied));
+    }
+
+    result
+}
+
+/// The length in bytes of the character at byte `offset` in `string`.
+#[cfg(feature = "stable-api")]
+unsafe fn char_len(string: VALUE, offset: std::os::raw::c_long) -> std::os::raw::c_long {
+    let len = crate::RSTRING_LEN(string);
+
+    if offset >= len {
+        return 1;
+    }
+
+    let ptr = crate::RSTRING_PTR(string);
+
+    crate::rb_enc_mbclen(
+        ptr.offset(offset as _),
+        ptr.offset(len as _),
+        crate::rb_enc_get(string),
+    ) as _
+}
+
+/// Returns a frozen, deduplicated UTF-8 string with the contents of `key`
+/// (akin to `-"key"`), for use as a `Hash` key.
+///
+/// Every call with the same contents returns the same object, so building
+/// many hashes with the same keys only keeps one copy of each key alive, and
+/// `Hash#[]=` does not need to dup and freeze the key.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::string::intern_key;
+///
+/// unsafe {
+///     let hash = rb_sys::rb_hash_new();
+///     rb_sys::rb_hash_aset(hash, intern_key("name"), rb_sys::Qnil as _);
+/// }
+/// ```
+pub unsafe fn intern_key(key: &str) -> VALUE {
+    let string = crate::rb_utf8_str_new(key.as_ptr() as _, key.len() as _);
+
+    #[cfg(ruby_gte_3_0)]
+    return crate::rb_str_to_interned_str(string);
+
+    #[cfg(not(ruby_gte_3_0))]
+    crate::rb_funcallv(string, crate::symbol::intern("-@"), 0, std::ptr::null())
+}
+
+/// Returns an unfrozen copy of `string` (akin to `String#dup`, via
+/// `rb_str_dup`), with the same contents, encoding and class.
+///
+/// Unlike [`crate::object::obj_dup`], it does not call `initialize_copy` or
+/// copy instance variables, so prefer it when only the string data matters.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `string` must be a Ruby `String`.
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::string::str_dup;
+///
+/// unsafe {
+///     let frozen = rb_sys::rb_str_new_frozen(rb_sys::rb_utf8_str_new("hi".as_ptr() as _, 2));
+///     let copy = str_dup(frozen);
+///
+///     rb_sys::rb_str_cat(copy, "!".as_ptr() as _, 1);
+/// }
+/// ```
+pub unsafe fn str_dup(string: VALUE) -> VALUE {
+    crate::rb_str_dup(string)
+}
+
+/// Returns a frozen copy of `string` (via `rb_str_new_frozen`), or `string`
+/// itself if it is already frozen.
+///
+/// The copy shares the buffer of `string` rather than copying it, and
+/// mutating `string` afterwards gives `string` its own buffer, so the copy
+/// never changes.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `string` must be a Ruby `String`.
+pub unsafe fn str_new_frozen(string: VALUE) -> VALUE {
+    crate::rb_str_new_frozen(string)
+}
+
+/// Takes a frozen snapshot of `string` (see [`str_new_frozen`]) whose bytes
+/// can be read for as long as the returned guard lives, even if `string` is
+/// mutated in the meantime (i.e. by a block or method called while reading).
+///
+/// This is a snapshot rather than a temporary freeze of `string` itself:
+/// Ruby's `rb_str_tmp_frozen_acquire` is internal, so `string` stays mutable
+/// and changes to it are not seen through the guard.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `string` must be a Ruby `String`, and the guard must be dropped on a thread
+/// which holds the GVL.
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::string::str_tmp_frozen;
+///
+/// unsafe {
+///     let string = rb_sys::rb_utf8_str_new("hello".as_ptr() as _, 5);
+///     let frozen = str_tmp_frozen(string);
+///
+///     rb_sys::rb_str_cat(string, " world".as_ptr() as _, 6);
+///
+///     assert_eq!(frozen.as_bytes(), b"hello");
+/// }
+/// ```
+#[cfg(feature = "stable-api")]
+pub unsafe fn str_tmp_frozen(string: VALUE) -> TmpFrozenStr {
+    let mut frozen = Box::new(str_new_frozen(string));
+
+    crate::rb_gc_register_address(&mut *frozen);
+
+    TmpFrozenStr { frozen }
+}
+
+/// A frozen snapshot of a string, returned by [`str_tmp_frozen`].
+///
+/// The snapshot is registered with the GC (via `rb_gc_register_address`)
+/// until the guard is dropped, so the guard can be stored anywhere, including
+/// on the heap.
+#[cfg(feature = "stable-api")]
+#[derive(Debug)]
+pub struct TmpFrozenStr {
+    // Boxed so the registered address does not change when the guard moves.
+    frozen: Box<VALUE>,
+}
+
+#[cfg(feature = "stable-api")]
+impl TmpFrozenStr {
+    /// The frozen snapshot.
+    pub fn as_value(&self) -> VALUE {
+        *self.frozen
+    }
+
+    /// The bytes of the snapshot, which do not change while the guard lives.
+    pub fn as_bytes(&self) -> &[u8] {
+        unsafe {
+            let ptr = crate::RSTRING_PTR(*self.frozen) as *const u8;
+            let len = crate::RSTRING_LEN(*self.frozen) as usize;
+
+            if len == 0 {
+                &[]
+            } else {
+                std::slice::from_raw_parts(ptr, len)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "stable-api")]
+impl Drop for TmpFrozenStr {
+    fn drop(&mut self) {
+        unsafe { crate::rb_gc_unregister_address(&mut *self.frozen) };
+    }
+}
+
+/// An encoding to transcode strings to with [`str_encode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    /// `UTF-8`
+    Utf8,
+    /// `US-ASCII`
+    UsAscii,
+    /// `ASCII-8BIT` (also known as `BINARY`)
+    Binary,
+    /// `UTF-16LE`
+    Utf16Le,
+    /// `UTF-16BE`
+    Utf16Be,
+    /// `ISO-8859-1`
+    Iso8859_1,
+    /// `Windows-1252`
+    Windows1252,
+    /// `Shift_JIS`
+    ShiftJis,
+    /// `EUC-JP`
+    EucJp,
+    /// Any other encoding known to Ruby, by name (i.e. `"GB18030"`).
+    Other(&'static str),
+}
+
+impl Encoding {
+    /// The name Ruby knows the encoding by.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Encoding::Utf8 => "UTF-8",
+            Encoding::UsAscii => "US-ASCII",
+            Encoding::Binary => "ASCII-8BIT",
+            Encoding::Utf16Le => "UTF-16LE",
+            Encoding::Utf16Be => "UTF-16BE",
+            Encoding::Iso8859_1 => "ISO-8859-1",
+            Encoding::Windows1252 => "Windows-1252",
+            Encoding::ShiftJis => "Shift_JIS",
+            Encoding::EucJp => "EUC-JP",
+            Encoding::Other(name) => name,
+        }
+    }
+}
+
+/// Transcodes `string` to the `to` encoding (akin to `String#encode`),
+/// returning a new string. `string` itself is left untouched.
+///
+/// Fails with an `EncodingError` if the encoding is unknown, if there is no
+/// converter between the two encodings, or if `string` contains characters
+/// which cannot be represented in `to`.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `string` must be a Ruby `String`.
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::string::{str_encode, Encoding};
+///
+/// unsafe {
+///     let string = rb_sys::rb_utf8_str_new("こんにちは".as_ptr() as _, 15);
+///     let sjis = str_encode(string, Encoding::ShiftJis).unwrap();
+/// }
+/// ```
+pub unsafe fn str_encode(string: VALUE, to: Encoding) -> Result<VALUE, RubyException> {
+    let name = to.name();
+
+    protect(|| {
+        // Ruby resolves the encoding name itself, raising if it is unknown.
+        let to = rb_str_new(name.as_ptr() as _, name.len() as _);
+
+        rb_str_encode(string, to, 0, Qnil as VALUE)
+    })
+}
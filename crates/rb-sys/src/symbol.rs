@@ -29,3 +29,85 @@ macro_rules! rb_intern {
         ID
     }};
 }
+
+/// Finds or creates the ID for the given string. Prefer [`rb_intern!`] when
+/// the name is known at compile time, since it memoizes the lookup.
+///
+/// Unlike [`rb_intern`](crate::rb_intern), the string does not need to be
+/// nul-terminated.
+///
+/// # Safety
+///
+/// The Ruby VM must be initialized, and this must be called from a Ruby thread.
+///
+/// # Example
+///
+/// ```no_run
+/// use rb_sys::symbol::intern;
+///
+/// let id = unsafe { intern("reverse") };
+/// ```
+pub unsafe fn intern(name: &str) -> crate::ID {
+    crate::rb_intern3(
+        name.as_ptr() as _,
+        name.len() as _,
+        crate::rb_utf8_encoding(),
+    )
+}
+
+/// Converts an array of strings to a new array of symbols (akin to
+/// `ary.map(&:to_sym)`). Symbols in `ary` are kept as they are.
+///
+/// Raises a `TypeError` if an element is neither a `String` nor a `Symbol`.
+///
+/// # Safety
+///
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `ary` must be a Ruby `Array`.
+///
+/// # Example
+///
+/// ```no_run
+/// use rb_sys::symbol::strings_to_symbols;
+///
+/// unsafe {
+///     let strings = rb_sys::rb_eval_string("['a', 'b']\0".as_ptr() as _);
+///     let symbols = strings_to_symbols(strings);
+/// }
+/// ```
+#[cfg(feature = "stable-api")]
+pub unsafe fn strings_to_symbols(ary: crate::VALUE) -> crate::VALUE {
+    map_array(ary, |element| crate::rb_to_symbol(element))
+}
+
+/// Converts an array of symbols to a new array of their names (akin to
+/// `ary.map(&:name)`). The names are frozen strings; use
+/// [`crate::string::str_dup`] to get a mutable copy.
+///
+/// Raises a `TypeError` if an element is neither a `Symbol` nor a `String`.
+///
+/// # Safety
+///
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `ary` must be a Ruby `Array`.
+#[cfg(feature = "stable-api")]
+pub unsafe fn symbols_to_strings(ary: crate::VALUE) -> crate::VALUE {
+    map_array(ary, |element| {
+        crate::rb_sym2str(crate::rb_to_symbol(element))
+    })
+}
+
+#[cfg(feature = "stable-api")]
+unsafe fn map_array<F>(ary: crate::VALUE, mut f: F) -> crate::VALUE
+where
+    F: FnMut(crate::VALUE) -> crate::VALUE,
+{
+    let len = crate::RARRAY_LEN(ary);
+    let result = crate::rb_ary_new_capa(len);
+
+    for i in 0..len {
+        crate::rb_ary_push(result, f(crate::rb_ary_entry(ary, i)));
+    }
+
+    result
+}
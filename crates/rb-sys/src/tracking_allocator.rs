@@ -1,5 +1,6 @@
 //! Support for reporting Rust memory usage to the Ruby GC.
 
+use crate::memory::size_delta;
 use std::{
     fmt::Formatter,
     sync::{
@@ -10,7 +11,7 @@ use std::{
 
 #[cfg(ruby_engine = "mri")]
 mod mri {
-    use crate::{rb_gc_adjust_memory_usage, utils::is_ruby_vm_started};
+    use crate::memory::{adjust_memory, size_delta};
     use std::alloc::{GlobalAlloc, Layout, System};
 
     /// A simple wrapper over [`System`] which reports memory usage to
@@ -47,24 +48,7 @@ mod mri {
         /// ```
         #[inline]
         pub fn adjust_memory_usage(delta: isize) -> isize {
-            if delta == 0 {
-                return 0;
-            }
-
-            #[cfg(target_pointer_width = "32")]
-            let delta = delta as i32;
-
-            #[cfg(target_pointer_width = "64")]
-            let delta = delta as i64;
-
-            unsafe {
-                if is_ruby_vm_started() {
-                    rb_gc_adjust_memory_usage(delta);
-                    delta as isize
-                } else {
-                    0
-                }
-            }
+            adjust_memory(delta)
         }
     }
 
@@ -72,7 +56,7 @@ mod mri {
         #[inline]
         unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
             let ret = System.alloc(layout);
-            let delta = layout.size() as isize;
+            let delta = size_delta(0, layout.size());
 
             if !ret.is_null() && delta != 0 {
                 Self::adjust_memory_usage(delta);
@@ -84,7 +68,7 @@ mod mri {
         #[inline]
         unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
             let ret = System.alloc_zeroed(layout);
-            let delta = layout.size() as isize;
+            let delta = size_delta(0, layout.size());
 
             if !ret.is_null() && delta != 0 {
                 Self::adjust_memory_usage(delta);
@@ -96,7 +80,7 @@ mod mri {
         #[inline]
         unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
             System.dealloc(ptr, layout);
-            let delta = -(layout.size() as isize);
+            let delta = size_delta(layout.size(), 0);
 
             if delta != 0 {
                 Self::adjust_memory_usage(delta);
@@ -106,7 +90,7 @@ mod mri {
         #[inline]
         unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
             let ret = System.realloc(ptr, layout, new_size);
-            let delta = new_size as isize - layout.size() as isize;
+            let delta = size_delta(layout.size(), new_size);
 
             if !ret.is_null() && delta != 0 {
                 Self::adjust_memory_usage(delta);
@@ -201,8 +185,8 @@ impl MemsizeDelta {
             return;
         }
 
-        let delta = TrackingAllocator::adjust_memory_usage(delta as _);
-        self.0.fetch_add(delta as _, Ordering::SeqCst);
+        let delta = TrackingAllocator::adjust_memory_usage(size_delta(0, delta));
+        self.0.fetch_add(delta, Ordering::SeqCst);
     }
 
     fn sub(&self, delta: usize) {
@@ -210,7 +194,7 @@ impl MemsizeDelta {
             return;
         }
 
-        let delta = TrackingAllocator::adjust_memory_usage(-(delta as isize));
+        let delta = TrackingAllocator::adjust_memory_usage(size_delta(delta, 0));
         self.0.fetch_add(delta, Ordering::SeqCst);
     }
 
@@ -263,7 +247,7 @@ impl<T> ManuallyTracked<T> {
     pub fn wrap(item: T, memsize: usize) -> Self {
         Self {
             item,
-            memsize_delta: MemsizeDelta::new(memsize as _),
+            memsize_delta: MemsizeDelta::new(size_delta(0, memsize)),
         }
     }
 
@@ -0,0 +1,196 @@
+//! Helpers for wrapping Rust values in Ruby objects, described by an
+//! `rb_data_type_t`.
+//!
+//! A type implements [`TypedData`] to describe how it is marked and copied,
+//! and is then wrapped with [`wrap`] and read back with [`get`]. Each object
+//! owns a `Box<Option<T>>`, which is `None` for objects created by
+//! `allocate` (see [`define_alloc`]) until they are initialized, i.e. by
+//! [`define_initialize_copy`] for `dup` and `clone`.
+
+use crate::class::define_method;
+use crate::exception::exception_new;
+use crate::object::{call_super, typed_data_wrap};
+use crate::{
+    rb_check_typeddata, rb_data_type_t, rb_define_alloc_func, rb_eTypeError, rb_exc_raise, VALUE,
+};
+use std::ffi::{c_void, CStr};
+
+/// A Rust type which can be wrapped in a Ruby object.
+///
+/// # Safety
+/// [`TypedData::data_type`] must always return the same `rb_data_type_t`,
+/// created with [`new_data_type`] for this type.
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::rb_data_type_t;
+/// use rb_sys::typed_data::{new_data_type, TypedData};
+///
+/// #[derive(Clone)]
+/// struct Point {
+///     x: i64,
+///     y: i64,
+/// }
+///
+/// unsafe impl TypedData for Point {
+///     fn data_type() -> &'static rb_data_type_t {
+///         static INIT: std::sync::Once = std::sync::Once::new();
+///         static mut DATA_TYPE: Option<&'static rb_data_type_t> = None;
+///
+///         unsafe {
+///             INIT.call_once(|| {
+///                 DATA_TYPE = Some(Box::leak(Box::new(new_data_type::<Point>("Point\0"))));
+///             });
+///
+///             DATA_TYPE.unwrap()
+///         }
+///     }
+///
+///     fn copy(&self) -> Option<Self> {
+///         Some(self.clone())
+///     }
+/// }
+/// ```
+pub unsafe trait TypedData: Sized + 'static {
+    /// The description of this type, which must be created with
+    /// [`new_data_type`] and live forever.
+    fn data_type() -> &'static rb_data_type_t;
+
+    /// Marks the Ruby objects referenced by this value, if any (i.e. with
+    /// `rb_gc_mark`). Called by the GC during the mark phase.
+    fn mark(&self) {}
+
+    /// Copies this value for `dup` and `clone` (see
+    /// [`define_initialize_copy`]), usually with `Some(self.clone())`. The
+    /// default returns `None`, which makes copying raise a `TypeError`.
+    fn copy(&self) -> Option<Self> {
+        None
+    }
+}
+
+/// Creates the `rb_data_type_t` for `T`, which marks and frees the wrapped
+/// values using [`TypedData`]. `name` is shown in error messages and
+/// must be nul-terminated (i.e. `"Point\0"`).
+///
+/// # Panics
+/// If `name` is not nul-terminated.
+pub fn new_data_type<T: TypedData>(name: &'static str) -> rb_data_type_t {
+    assert!(
+        name.ends_with('\0'),
+        "data type name {:?} must be nul-terminated",
+        name
+    );
+
+    let mut data_type: rb_data_type_t = unsafe { std::mem::zeroed() };
+    data_type.wrap_struct_name = name.as_ptr() as _;
+    data_type.function.dmark = Some(mark::<T>);
+    data_type.function.dfree = Some(free::<T>);
+    data_type
+}
+
+/// Wraps `value` in a new instance of `klass`.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `klass` must be a Ruby `Class`.
+pub unsafe fn wrap<T: TypedData>(klass: VALUE, value: T) -> VALUE {
+    let data = Box::into_raw(Box::new(Some(value)));
+
+    typed_data_wrap(klass, data as *mut c_void, T::data_type())
+}
+
+/// Returns the value wrapped by `obj`, or `None` if it has not been
+/// initialized yet. Raises a `TypeError` if `obj` does not wrap a `T`.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// The returned reference must not outlive `obj`, or be used alongside
+/// another reference to the same value.
+pub unsafe fn get<'a, T: TypedData>(obj: VALUE) -> Option<&'a mut T> {
+    (*cell::<T>(obj)).as_mut()
+}
+
+/// Defines the allocation function of `klass`, so `klass.allocate` (and
+/// `dup` and `clone`) create uninitialized instances wrapping a `T`.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `klass` must be a Ruby `Class`.
+pub unsafe fn define_alloc<T: TypedData>(klass: VALUE) {
+    rb_define_alloc_func(klass, Some(alloc::<T>));
+}
+
+/// Defines `initialize_copy` on `klass` to copy the wrapped value with
+/// [`TypedData::copy`], so `dup` and `clone` return an independent copy.
+/// `klass` also needs an allocation function from [`define_alloc`].
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+/// `klass` must be a Ruby `Class` whose instances wrap a `T`.
+///
+/// # Example
+/// ```no_run
+/// use rb_sys::typed_data::{define_alloc, define_initialize_copy, wrap, TypedData};
+///
+/// unsafe fn init<T: TypedData>(klass: rb_sys::VALUE, value: T) -> rb_sys::VALUE {
+///     define_alloc::<T>(klass);
+///     define_initialize_copy::<T>(klass);
+///
+///     // `dup` and `clone` of this object now copy `value`
+///     wrap(klass, value)
+/// }
+/// ```
+pub unsafe fn define_initialize_copy<T: TypedData>(klass: VALUE) {
+    let initialize_copy: unsafe extern "C" fn(VALUE, VALUE) -> VALUE = initialize_copy::<T>;
+
+    define_method(klass, "initialize_copy", initialize_copy);
+}
+
+unsafe fn cell<T: TypedData>(obj: VALUE) -> *mut Option<T> {
+    rb_check_typeddata(obj, T::data_type()) as *mut Option<T>
+}
+
+unsafe extern "C" fn alloc<T: TypedData>(klass: VALUE) -> VALUE {
+    let data = Box::into_raw(Box::new(None::<T>));
+
+    typed_data_wrap(klass, data as *mut c_void, T::data_type())
+}
+
+unsafe extern "C" fn initialize_copy<T: TypedData>(copy: VALUE, orig: VALUE) -> VALUE {
+    if copy == orig {
+        return copy;
+    }
+
+    // Checks that `copy` is not frozen and has the same class as `orig`.
+    call_super(&[orig]);
+
+    let value = match &*cell::<T>(orig) {
+        Some(value) => match value.copy() {
+            Some(copied) => Some(copied),
+            None => {
+                let name = CStr::from_ptr(T::data_type().wrap_struct_name);
+                let message = format!("can't copy {}", name.to_string_lossy());
+                let error = exception_new(rb_eTypeError, &message);
+
+                // Raising skips destructors, so `message` would leak.
+                drop(message);
+                rb_exc_raise(error)
+            }
+        },
+        None => None,
+    };
+
+    *cell::<T>(copy) = value;
+
+    copy
+}
+
+unsafe extern "C" fn mark<T: TypedData>(data: *mut c_void) {
+    if let Some(value) = &*(data as *const Option<T>) {
+        value.mark();
+    }
+}
+
+unsafe extern "C" fn free<T: TypedData>(data: *mut c_void) {
+    drop(Box::from_raw(data as *mut Option<T>));
+}
@@ -38,6 +38,26 @@ pub(crate) unsafe fn is_ruby_vm_started() -> bool {
     ret
 }
 
+/// Converts `s` into a nul-terminated C string, raising an `ArgumentError`
+/// (like Ruby itself does) if it contains an interior nul byte.
+///
+/// # Safety
+/// The Ruby VM must be initialized and the current thread must hold the GVL.
+#[allow(unreachable_code)]
+pub(crate) unsafe fn cstring_or_raise(s: String) -> std::ffi::CString {
+    match std::ffi::CString::new(s) {
+        Ok(s) => s,
+        Err(err) => {
+            drop(err);
+            crate::rb_raise(
+                crate::rb_eArgError,
+                "string contains null byte\0".as_ptr() as _,
+            );
+            unreachable!()
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;